@@ -0,0 +1,62 @@
+"""Regenerate `src/classifiers.txt` from the `trove-classifiers` package.
+
+The file lists every classifier PyPI accepts, including deprecated ones, sorted and one per
+line, for the `classifiers` feature.
+
+Usage:
+
+    pip install -U trove-classifiers
+    python scripts/update_classifiers.py          # rewrite src/classifiers.txt
+    python scripts/update_classifiers.py --check  # fail if src/classifiers.txt is stale
+"""
+
+import argparse
+import sys
+from importlib.metadata import version
+from pathlib import Path
+
+from trove_classifiers import classifiers, deprecated_classifiers
+
+CLASSIFIERS_TXT = Path(__file__).parent.parent.joinpath("src", "classifiers.txt")
+
+
+def entries(content: str) -> list[str]:
+    """The classifiers in the file, without the header comment."""
+    return [line for line in content.splitlines() if line and not line.startswith("#")]
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+    parser.add_argument(
+        "--check",
+        action="store_true",
+        help="Exit with an error if the file doesn't match the installed package",
+    )
+    args = parser.parse_args()
+
+    expected = sorted(set(classifiers) | set(deprecated_classifiers))
+    if args.check:
+        current = entries(CLASSIFIERS_TXT.read_text(encoding="utf-8"))
+        if current != expected:
+            missing = sorted(set(expected) - set(current))
+            extra = sorted(set(current) - set(expected))
+            for classifier in missing:
+                print(f"missing: {classifier}", file=sys.stderr)
+            for classifier in extra:
+                print(f"unknown: {classifier}", file=sys.stderr)
+            print(
+                f"{CLASSIFIERS_TXT} is stale, run `python scripts/update_classifiers.py`",
+                file=sys.stderr,
+            )
+            sys.exit(1)
+        return
+
+    header = (
+        f"# Generated by scripts/update_classifiers.py from trove-classifiers "
+        f"{version('trove-classifiers')}, do not edit\n"
+    )
+    CLASSIFIERS_TXT.write_text(header + "".join(f"{c}\n" for c in expected), encoding="utf-8")
+
+
+if __name__ == "__main__":
+    main()
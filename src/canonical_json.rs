@@ -0,0 +1,220 @@
+//! A canonical JSON representation for hashing and comparing across languages.
+
+use serde_json::Value;
+
+use crate::PyProjectToml;
+
+/// Write a JSON value with sorted object keys, without whitespace and without `null` members.
+fn write_canonical(value: &Value, output: &mut String) {
+    match value {
+        Value::Array(items) => {
+            output.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                write_canonical(item, output);
+            }
+            output.push(']');
+        }
+        Value::Object(members) => {
+            let mut members: Vec<(&String, &Value)> = members
+                .iter()
+                .filter(|(_, value)| !value.is_null())
+                .collect();
+            members.sort_by_key(|(key, _)| *key);
+            output.push('{');
+            for (index, (key, value)) in members.into_iter().enumerate() {
+                if index > 0 {
+                    output.push(',');
+                }
+                output.push_str(&Value::String(key.clone()).to_string());
+                output.push(':');
+                write_canonical(value, output);
+            }
+            output.push('}');
+        }
+        scalar => output.push_str(&scalar.to_string()),
+    }
+}
+
+impl PyProjectToml {
+    /// Convert to a JSON value, e.g. to hand the parsed project to an editor or language server.
+    ///
+    /// Keys are the kebab-case `pyproject.toml` keys and unset fields are `null`. Requirements,
+    /// versions and version specifiers are strings in their normalized PEP 508 and PEP 440 forms:
+    ///
+    /// ```
+    /// # use pyproject_toml::PyProjectToml;
+    /// let pyproject_toml = PyProjectToml::new(
+    ///     r#"[project]
+    /// name = "spam"
+    /// version = "1.0"
+    /// dependencies = ["Django >= 2"]
+    /// "#,
+    /// )
+    /// .unwrap();
+    /// let json = pyproject_toml.to_json();
+    /// assert_eq!(json["project"]["version"], "1.0");
+    /// assert_eq!(json["project"]["dependencies"][0], "django>=2");
+    /// ```
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).expect("pyproject.toml is valid JSON")
+    }
+
+    /// Serialize to a canonical JSON string, e.g. for computing the same hash in another language.
+    ///
+    /// The canonicalization rules are:
+    ///
+    /// * Keys are the kebab-case `pyproject.toml` keys, e.g. `requires-python`.
+    /// * Object members are sorted by key, comparing the UTF-8 bytes.
+    /// * Unset fields are omitted instead of being written as `null`.
+    /// * Arrays keep their order.
+    /// * Requirements, versions and version specifiers are written in their normalized PEP 508 and
+    ///   PEP 440 forms, e.g. `Django >= 2` becomes `django>=2`.
+    /// * Strings use JSON escapes only where required, and there is no whitespace between tokens.
+    pub fn to_canonical_json(&self) -> String {
+        self.canonical_json_without(&[])
+    }
+
+    /// Compute a SHA-256 hash of the canonical JSON form, e.g. as a build cache key, leaving out
+    /// the top-level tables named in `ignore`.
+    ///
+    /// The recognized names are `build-system`, `project`, `dependency-groups` and `tool`; other
+    /// names are ignored. For example, ignoring `build-system` gives a fingerprint that only
+    /// changes with the runtime metadata.
+    #[cfg(feature = "fingerprint")]
+    pub fn fingerprint(&self, ignore: &[&str]) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
+
+        Sha256::digest(self.canonical_json_without(ignore)).into()
+    }
+
+    fn canonical_json_without(&self, ignore: &[&str]) -> String {
+        let mut value = self.to_json();
+        if let Value::Object(tables) = &mut value {
+            for name in ignore {
+                tables.remove(*name);
+            }
+        }
+        let mut output = String::new();
+        write_canonical(&value, &mut output);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::PyProjectToml;
+
+    #[test]
+    fn test_to_json() {
+        let source = r#"[build-system]
+requires = ["maturin"]
+build-backend = "maturin"
+
+[project]
+name = "spam"
+version = "2020.0.0"
+requires-python = ">=3.8"
+dependencies = [
+  "httpx",
+  "gidgethub[httpx]>4.0.0",
+  "django>2.1; os_name != 'nt'",
+  "django>2.0; os_name == 'nt'"
+]
+
+[project.optional-dependencies]
+test = ["pytest < 5.0.0", "pytest-cov[all]"]
+
+[project.scripts]
+spam-cli = "spam:main_cli"
+"#;
+        let pyproject_toml = PyProjectToml::new(source).unwrap();
+        let json = pyproject_toml.to_json();
+        assert_eq!(
+            json["build-system"],
+            json!({"requires": ["maturin"], "build-backend": "maturin", "backend-path": null})
+        );
+        let project = &json["project"];
+        assert_eq!(project["name"], "spam");
+        assert_eq!(project["version"], "2020.0.0");
+        assert_eq!(project["requires-python"], ">=3.8");
+        assert_eq!(
+            project["dependencies"],
+            json!([
+                "httpx",
+                "gidgethub[httpx]>4.0.0",
+                "django>2.1 ; os_name != 'nt'",
+                "django>2.0 ; os_name == 'nt'"
+            ])
+        );
+        assert_eq!(
+            project["optional-dependencies"],
+            json!({"test": ["pytest<5.0.0", "pytest-cov[all]"]})
+        );
+        assert_eq!(project["scripts"], json!({"spam-cli": "spam:main_cli"}));
+        assert_eq!(json["dependency-groups"], json!(null));
+    }
+
+    #[test]
+    fn test_canonical_json() {
+        let first = PyProjectToml::new(
+            r#"[project]
+name = "spam"
+version = "1.0"
+dependencies = ["Django >= 2", "httpx"]
+
+[build-system]
+requires = ["maturin"]
+build-backend = "maturin"
+"#,
+        )
+        .unwrap();
+        let second = PyProjectToml::new(
+            r#"[build-system]
+build-backend = "maturin"
+requires = ["maturin"]
+
+[project]
+dependencies = ["django>=2", "httpx"]
+version = "1.0"
+name = "spam"
+"#,
+        )
+        .unwrap();
+        assert_eq!(first.to_canonical_json(), second.to_canonical_json());
+        assert_eq!(
+            first.to_canonical_json(),
+            r#"{"build-system":{"build-backend":"maturin","requires":["maturin"]},"project":{"dependencies":["django>=2","httpx"],"name":"spam","version":"1.0"}}"#
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "fingerprint")]
+    fn test_fingerprint() {
+        let project = r#"[project]
+name = "spam"
+version = "1.0"
+"#;
+        let first = PyProjectToml::new(&format!(
+            "{project}\n[build-system]\nrequires = [\"maturin\"]\n"
+        ))
+        .unwrap();
+        let second = PyProjectToml::new(&format!(
+            "{project}\n[build-system]\nrequires = [\"setuptools\"]\n"
+        ))
+        .unwrap();
+        assert_ne!(first.fingerprint(&[]), second.fingerprint(&[]));
+        assert_eq!(
+            first.fingerprint(&["build-system"]),
+            second.fingerprint(&["build-system"])
+        );
+        assert_ne!(
+            first.fingerprint(&["build-system"]),
+            first.fingerprint(&["build-system", "project"])
+        );
+    }
+}
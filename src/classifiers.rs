@@ -0,0 +1,92 @@
+//! Checking `project.classifiers` against the trove classifiers accepted by PyPI.
+
+use crate::Project;
+
+/// The known trove classifiers, one per line and sorted, including deprecated ones, generated by
+/// `scripts/update_classifiers.py` from the `trove-classifiers` package. Lines starting with `#`
+/// are comments.
+const CLASSIFIERS: &str = include_str!("classifiers.txt");
+
+/// The classifiers in [`CLASSIFIERS`], without comments.
+fn known_classifiers() -> impl Iterator<Item = &'static str> {
+    CLASSIFIERS
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// Whether `classifier` is a known trove classifier.
+fn is_known_classifier(classifier: &str) -> bool {
+    known_classifiers().any(|known| known == classifier)
+}
+
+impl Project {
+    /// Check `project.classifiers` against the list of trove classifiers, since PyPI rejects
+    /// uploads with unknown classifiers.
+    ///
+    /// Returns all unknown classifiers in the order they are listed. Deprecated classifiers are
+    /// still accepted. Classifiers starting with `Private ::` are reserved for packages that must
+    /// not be uploaded and are reported too.
+    pub fn validate_classifiers(&self) -> Result<(), Vec<String>> {
+        let unknown: Vec<String> = self
+            .classifiers
+            .iter()
+            .flatten()
+            .filter(|classifier| !is_known_classifier(classifier))
+            .cloned()
+            .collect();
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            Err(unknown)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::known_classifiers;
+    use crate::PyProjectToml;
+
+    /// The list must be the sorted output of `scripts/update_classifiers.py`, CI checks it against
+    /// the latest `trove-classifiers` release.
+    #[test]
+    fn test_classifiers_sorted() {
+        let classifiers: Vec<&str> = known_classifiers().collect();
+        assert!(classifiers.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_validate_classifiers() {
+        let source = r#"[project]
+name = "spam"
+classifiers = [
+  "Development Status :: 4 - Beta",
+  "License :: OSI Approved :: MIT License",
+  "Programming Language :: Python :: 3.12",
+  "Natural Language :: Ukranian",
+  "Typing :: Typed",
+]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let project = project_toml.project.as_ref().unwrap();
+        assert_eq!(project.validate_classifiers(), Ok(()));
+
+        let source = r#"[project]
+name = "spam"
+classifiers = [
+  "Programming Language :: Pyton",
+  "Programming Language :: Python",
+  "Private :: Do Not Upload",
+]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let project = project_toml.project.as_ref().unwrap();
+        assert_eq!(
+            project.validate_classifiers(),
+            Err(vec![
+                "Programming Language :: Pyton".to_string(),
+                "Private :: Do Not Upload".to_string()
+            ])
+        );
+    }
+}
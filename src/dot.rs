@@ -0,0 +1,106 @@
+//! Rendering of the `[dependency-groups]` include graph in the Graphviz DOT format.
+
+use pep508_rs::PackageName;
+use std::str::FromStr;
+
+use crate::resolution::self_reference;
+use crate::{DependencyGroupSpecifier, DependencyGroups};
+
+/// Quote a node name as a DOT identifier.
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl DependencyGroups {
+    /// Render the groups and their `include-group` edges as a Graphviz DOT digraph, e.g. for
+    /// `dot -Tpng`.
+    pub fn to_dot(&self) -> String {
+        self.render_dot(None)
+    }
+
+    /// Like [`DependencyGroups::to_dot`], but also render self-references to extras of the project
+    /// (such as `spam[test]`) as dashed edges to boxed `extra:<name>` nodes.
+    ///
+    /// `include-extra` entries are rendered the same way by both functions.
+    pub fn to_dot_with_name(&self, project_name: &str) -> String {
+        self.render_dot(PackageName::from_str(project_name).ok().as_ref())
+    }
+
+    fn render_dot(&self, project_name: Option<&PackageName>) -> String {
+        let mut lines = vec!["digraph dependency_groups {".to_string()];
+        for group in self.keys() {
+            lines.push(format!("    {};", quote(group)));
+        }
+        for (group, specifiers) in self.iter() {
+            for specifier in specifiers {
+                match specifier {
+                    DependencyGroupSpecifier::Table { include_group } => {
+                        lines.push(format!("    {} -> {};", quote(group), quote(include_group)));
+                    }
+                    // Requirements files are not part of the graph
+                    #[cfg(feature = "requirements-txt")]
+                    DependencyGroupSpecifier::Path { .. } => {}
+                    #[cfg(feature = "include-extra")]
+                    DependencyGroupSpecifier::IncludeExtra { include_extra } => {
+                        let extra = quote(&format!("extra:{include_extra}"));
+                        lines.push(format!("    {extra} [shape=box];"));
+                        lines.push(format!("    {} -> {extra} [style=dashed];", quote(group)));
+                    }
+                    DependencyGroupSpecifier::String(requirement) => {
+                        let Some(project_name) = project_name else {
+                            continue;
+                        };
+                        for extra in self_reference(requirement, project_name)
+                            .into_iter()
+                            .flatten()
+                        {
+                            let extra = quote(&format!("extra:{extra}"));
+                            lines.push(format!("    {extra} [shape=box];"));
+                            lines.push(format!("    {} -> {extra} [style=dashed];", quote(group)));
+                        }
+                    }
+                }
+            }
+        }
+        lines.push("}".to_string());
+        lines.join("\n") + "\n"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use crate::PyProjectToml;
+
+    #[test]
+    fn test_to_dot() {
+        let source = r#"[dependency-groups]
+test = ["pytest", "spam[typing]"]
+lint = ["ruff"]
+dev = [{include-group = "test"}, {include-group = "lint"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let dependency_groups = project_toml.dependency_groups.unwrap();
+        assert_snapshot!(dependency_groups.to_dot(), @r#"
+        digraph dependency_groups {
+            "test";
+            "lint";
+            "dev";
+            "dev" -> "test";
+            "dev" -> "lint";
+        }
+        "#);
+        assert_snapshot!(dependency_groups.to_dot_with_name("spam"), @r#"
+        digraph dependency_groups {
+            "test";
+            "lint";
+            "dev";
+            "extra:typing" [shape=box];
+            "test" -> "extra:typing" [style=dashed];
+            "dev" -> "test";
+            "dev" -> "lint";
+        }
+        "#);
+    }
+}
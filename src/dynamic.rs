@@ -0,0 +1,347 @@
+//! Validation of the `project.dynamic` field.
+
+use pep440_rs::Version;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::resolution::self_reference;
+use crate::{Project, PyProjectToml};
+
+/// The `[project]` fields that PEP 621 forbids listing in `dynamic`.
+const NEVER_DYNAMIC: &[&str] = &["name"];
+
+/// An invalid `project.dynamic` entry
+#[derive(Debug, Error)]
+pub enum DynamicError {
+    #[error("`name` must not be dynamic")]
+    Name,
+    #[error("`{0}` is not a `[project]` field")]
+    UnknownField(String),
+    #[error("`{0}` is set statically, so it must not be dynamic")]
+    AlreadyStatic(String),
+}
+
+/// A `[project]` field that may be listed in `dynamic`, i.e. any field except `name`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DynamicField {
+    Version,
+    Description,
+    Readme,
+    RequiresPython,
+    License,
+    LicenseFiles,
+    Authors,
+    Maintainers,
+    Keywords,
+    Classifiers,
+    Urls,
+    EntryPoints,
+    Scripts,
+    GuiScripts,
+    Dependencies,
+    OptionalDependencies,
+}
+
+/// The state of `project.version`, returned by [`Project::resolved_version`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionStatus {
+    /// The version is set in `pyproject.toml`
+    Static(Version),
+    /// The version is listed in `dynamic`, the build backend provides it
+    Dynamic,
+    /// The version is neither set nor dynamic, which PEP 621 doesn't allow
+    Missing,
+}
+
+impl DynamicField {
+    /// The kebab-case field name, e.g. `requires-python`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DynamicField::Version => "version",
+            DynamicField::Description => "description",
+            DynamicField::Readme => "readme",
+            DynamicField::RequiresPython => "requires-python",
+            DynamicField::License => "license",
+            DynamicField::LicenseFiles => "license-files",
+            DynamicField::Authors => "authors",
+            DynamicField::Maintainers => "maintainers",
+            DynamicField::Keywords => "keywords",
+            DynamicField::Classifiers => "classifiers",
+            DynamicField::Urls => "urls",
+            DynamicField::EntryPoints => "entry-points",
+            DynamicField::Scripts => "scripts",
+            DynamicField::GuiScripts => "gui-scripts",
+            DynamicField::Dependencies => "dependencies",
+            DynamicField::OptionalDependencies => "optional-dependencies",
+        }
+    }
+}
+
+impl FromStr for DynamicField {
+    type Err = DynamicError;
+
+    fn from_str(field: &str) -> Result<Self, Self::Err> {
+        let dynamic_field = match field {
+            "name" => return Err(DynamicError::Name),
+            "version" => DynamicField::Version,
+            "description" => DynamicField::Description,
+            "readme" => DynamicField::Readme,
+            "requires-python" => DynamicField::RequiresPython,
+            "license" => DynamicField::License,
+            "license-files" => DynamicField::LicenseFiles,
+            "authors" => DynamicField::Authors,
+            "maintainers" => DynamicField::Maintainers,
+            "keywords" => DynamicField::Keywords,
+            "classifiers" => DynamicField::Classifiers,
+            "urls" => DynamicField::Urls,
+            "entry-points" => DynamicField::EntryPoints,
+            "scripts" => DynamicField::Scripts,
+            "gui-scripts" => DynamicField::GuiScripts,
+            "dependencies" => DynamicField::Dependencies,
+            "optional-dependencies" => DynamicField::OptionalDependencies,
+            _ => return Err(DynamicError::UnknownField(field.to_string())),
+        };
+        Ok(dynamic_field)
+    }
+}
+
+impl Display for DynamicField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Project {
+    /// Whether the `[project]` field with the given (kebab-case) name has a static value.
+    ///
+    /// Returns `None` if there is no such field.
+    pub(crate) fn is_static(&self, field: &str) -> Option<bool> {
+        let is_static = match field {
+            "name" => true,
+            "version" => self.version.is_some(),
+            "description" => self.description.is_some(),
+            "readme" => self.readme.is_some(),
+            "requires-python" => self.requires_python.is_some(),
+            "license" => self.license.is_some(),
+            "license-files" => self.license_files.is_some(),
+            "authors" => self.authors.is_some(),
+            "maintainers" => self.maintainers.is_some(),
+            "keywords" => self.keywords.is_some(),
+            "classifiers" => self.classifiers.is_some(),
+            "urls" => self.urls.is_some(),
+            "entry-points" => self.entry_points.is_some(),
+            "scripts" => self.scripts.is_some(),
+            "gui-scripts" => self.gui_scripts.is_some(),
+            "dependencies" => self.dependencies.is_some(),
+            "optional-dependencies" => self.optional_dependencies.is_some(),
+            _ => return None,
+        };
+        Some(is_static)
+    }
+
+    /// Whether the field is listed in `dynamic`.
+    pub fn is_dynamic(&self, field: &str) -> bool {
+        self.dynamic
+            .iter()
+            .flatten()
+            .any(|dynamic| dynamic == field)
+    }
+
+    /// Whether the version is set statically, is provided by the build backend, or is missing.
+    ///
+    /// A version that is set and also listed in `dynamic` is returned as static, see
+    /// [`Project::validate_dynamic`] for reporting that conflict.
+    pub fn resolved_version(&self) -> VersionStatus {
+        match &self.version {
+            Some(version) => VersionStatus::Static(version.clone()),
+            None if self.is_dynamic("version") => VersionStatus::Dynamic,
+            None => VersionStatus::Missing,
+        }
+    }
+
+    /// Check the `dynamic` entries, returning them as [`DynamicField`]s.
+    ///
+    /// Returns an error for the first entry that is `name`, isn't a `[project]` field, or is a
+    /// field that is also set statically.
+    pub fn validate_dynamic(&self) -> Result<Vec<DynamicField>, DynamicError> {
+        let mut fields = Vec::new();
+        for entry in self.dynamic.iter().flatten() {
+            let field = DynamicField::from_str(entry)?;
+            if self.is_static(field.as_str()) == Some(true) {
+                return Err(DynamicError::AlreadyStatic(entry.clone()));
+            }
+            fields.push(field);
+        }
+        Ok(fields)
+    }
+
+    /// Return the `dynamic` entries that can never be dynamic, such as `name`.
+    ///
+    /// Unlike a field that is both set and listed in `dynamic`, these are invalid regardless of
+    /// the rest of the `[project]` table.
+    pub fn illegal_dynamic_fields(&self) -> Vec<&str> {
+        self.dynamic
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|field| NEVER_DYNAMIC.contains(field))
+            .collect()
+    }
+
+    /// Add a field to `dynamic`, for a backend to fill in.
+    ///
+    /// The field must be a `[project]` field other than `name` and must not be set statically.
+    /// Marking a field that is already dynamic does nothing.
+    pub fn mark_dynamic(&mut self, field: &str) -> Result<(), DynamicError> {
+        match self.is_static(field) {
+            None => return Err(DynamicError::UnknownField(field.to_string())),
+            Some(_) if NEVER_DYNAMIC.contains(&field) => return Err(DynamicError::Name),
+            Some(true) => return Err(DynamicError::AlreadyStatic(field.to_string())),
+            Some(false) => {}
+        }
+        if !self.is_dynamic(field) {
+            self.dynamic
+                .get_or_insert_with(Vec::new)
+                .push(field.to_string());
+        }
+        Ok(())
+    }
+}
+
+impl PyProjectToml {
+    /// Return warnings about self-references, such as `spam[test]` in the dependency groups of
+    /// `spam`, that resolution can't fully expand because the referenced fields are dynamic.
+    ///
+    /// The checked conditions are:
+    ///
+    /// * `dependencies` is dynamic and a self-reference pulls in the base dependencies, which are
+    ///   then only known after building the project.
+    /// * `optional-dependencies` is dynamic and a self-reference requests extras, which are then
+    ///   only known after building the project.
+    pub fn dynamic_dependency_warnings(&self) -> Vec<String> {
+        let Some(project) = &self.project else {
+            return Vec::new();
+        };
+        let Ok(project_name) = project.normalized_name() else {
+            return Vec::new();
+        };
+        let dynamic_dependencies = project.is_dynamic("dependencies");
+        let dynamic_extras = project.is_dynamic("optional-dependencies");
+
+        let mut warnings = Vec::new();
+        for (source, requirement) in self.requirements_with_source() {
+            let Some(extras) = self_reference(requirement, &project_name) else {
+                continue;
+            };
+            if dynamic_dependencies {
+                warnings.push(format!(
+                    "`{requirement}` in `{source}` includes the dynamic `dependencies` of the project"
+                ));
+            }
+            if dynamic_extras && !extras.is_empty() {
+                warnings.push(format!(
+                    "`{requirement}` in `{source}` requests extras, but `optional-dependencies` is dynamic"
+                ));
+            }
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pep440_rs::Version;
+    use std::str::FromStr;
+
+    use super::{DynamicField, VersionStatus};
+    use crate::{Project, PyProjectToml};
+
+    #[test]
+    fn test_mark_dynamic() {
+        let mut project = Project::new("spam".to_string());
+        project.description = Some("Lovely Spam! Wonderful Spam!".to_string());
+
+        assert!(!project.is_dynamic("version"));
+        project.mark_dynamic("version").unwrap();
+        project.mark_dynamic("version").unwrap();
+        assert!(project.is_dynamic("version"));
+        assert_eq!(project.dynamic, Some(vec!["version".to_string()]));
+
+        assert_eq!(
+            project.mark_dynamic("name").unwrap_err().to_string(),
+            "`name` must not be dynamic"
+        );
+        assert_eq!(
+            project.mark_dynamic("description").unwrap_err().to_string(),
+            "`description` is set statically, so it must not be dynamic"
+        );
+        assert_eq!(
+            project.mark_dynamic("versoin").unwrap_err().to_string(),
+            "`versoin` is not a `[project]` field"
+        );
+    }
+
+    #[test]
+    fn test_validate_dynamic() {
+        let mut project = Project::new("spam".to_string());
+        project.dynamic = Some(vec!["version".to_string(), "requires-python".to_string()]);
+        assert_eq!(
+            project.validate_dynamic().unwrap(),
+            vec![DynamicField::Version, DynamicField::RequiresPython]
+        );
+
+        project.dynamic = Some(vec!["version".to_string(), "name".to_string()]);
+        assert_eq!(
+            project.validate_dynamic().unwrap_err().to_string(),
+            "`name` must not be dynamic"
+        );
+
+        project.description = Some("Lovely Spam! Wonderful Spam!".to_string());
+        project.dynamic = Some(vec!["description".to_string()]);
+        assert_eq!(
+            project.validate_dynamic().unwrap_err().to_string(),
+            "`description` is set statically, so it must not be dynamic"
+        );
+    }
+
+    #[test]
+    fn test_resolved_version() {
+        let mut project = Project::new("spam".to_string());
+        assert_eq!(project.resolved_version(), VersionStatus::Missing);
+
+        project.mark_dynamic("version").unwrap();
+        assert_eq!(project.resolved_version(), VersionStatus::Dynamic);
+
+        let version = Version::from_str("1.0").unwrap();
+        project.dynamic = None;
+        project.version = Some(version.clone());
+        assert_eq!(project.resolved_version(), VersionStatus::Static(version));
+    }
+
+    #[test]
+    fn test_illegal_dynamic_fields() {
+        let mut project = Project::new("spam".to_string());
+        project.dynamic = Some(vec!["version".to_string(), "name".to_string()]);
+        assert_eq!(project.illegal_dynamic_fields(), vec!["name"]);
+    }
+
+    #[test]
+    fn test_dynamic_dependency_warnings() {
+        let source = r#"[project]
+name = "spam"
+dynamic = ["dependencies"]
+
+[project.optional-dependencies]
+test = ["pytest"]
+all = ["spam[test]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        assert_eq!(
+            project_toml.dynamic_dependency_warnings(),
+            vec![
+                "`spam[test]` in `project.optional-dependencies.all` includes the dynamic `dependencies` of the project"
+            ]
+        );
+    }
+}
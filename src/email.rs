@@ -0,0 +1,286 @@
+//! Validation of `project.authors` and `project.maintainers` email addresses.
+
+use std::fmt::{Display, Formatter};
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+use thiserror::Error;
+
+use crate::Contact;
+
+/// An invalid `project.authors` or `project.maintainers` entry
+#[derive(Debug, Error)]
+pub enum ContactError {
+    #[error("`{email}` is not a valid email address: {reason}")]
+    InvalidEmail { email: String, reason: String },
+    #[error("`{0}` is not a valid contact, expected `Name <email>`, `Name` or `<email>`")]
+    InvalidFormat(String),
+    #[error("A contact needs a name or an email, but both are empty")]
+    Empty,
+    #[error("The name of a contact must not be empty")]
+    EmptyName,
+    #[error("The email of a contact must not be empty")]
+    EmptyEmail,
+}
+
+/// Display a contact as in core metadata, e.g., `John Doe <john.doe@example.net>`.
+impl Display for Contact {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Contact::NameEmail { name, email } => write!(f, "{name} <{email}>"),
+            Contact::Name { name } => write!(f, "{name}"),
+            Contact::Email { email } => write!(f, "<{email}>"),
+        }
+    }
+}
+
+/// Parse the `Name <email>`, `Name` and `<email>` forms written by the [`Display`] implementation.
+///
+/// The email is not validated, see [`Contact::validate`].
+impl FromStr for Contact {
+    type Err = ContactError;
+
+    fn from_str(contact: &str) -> Result<Self, Self::Err> {
+        let invalid = || ContactError::InvalidFormat(contact.to_string());
+        let trimmed = contact.trim();
+        let Some(rest) = trimmed.strip_suffix('>') else {
+            if trimmed.is_empty() || trimmed.contains(['<', '>']) {
+                return Err(invalid());
+            }
+            return Ok(Contact::Name {
+                name: trimmed.to_string(),
+            });
+        };
+        let (name, email) = rest.rsplit_once('<').ok_or_else(invalid)?;
+        let name = name.trim();
+        if email.is_empty() || email.contains(['<', '>']) || name.contains(['<', '>']) {
+            return Err(invalid());
+        }
+        if name.is_empty() {
+            Ok(Contact::Email {
+                email: email.to_string(),
+            })
+        } else {
+            Ok(Contact::NameEmail {
+                name: name.to_string(),
+                email: email.to_string(),
+            })
+        }
+    }
+}
+
+impl Contact {
+    /// Check that the email, if any, is a valid RFC 822 `addr-spec` such as
+    /// `john.doe@example.net`, which is what Python's `email.headerregistry` expects in core
+    /// metadata.
+    ///
+    /// Names and emails must not be empty or only whitespace, a contact with only a name is
+    /// otherwise always valid.
+    pub fn validate(&self) -> Result<(), ContactError> {
+        let is_empty = |value: Option<&str>| value.map(|value| value.trim().is_empty());
+        match (is_empty(self.name()), is_empty(self.email())) {
+            (Some(true), Some(true)) => return Err(ContactError::Empty),
+            (Some(true), _) => return Err(ContactError::EmptyName),
+            (_, Some(true)) => return Err(ContactError::EmptyEmail),
+            _ => {}
+        }
+        let Some(email) = self.email() else {
+            return Ok(());
+        };
+        AddrSpecParser {
+            chars: email.chars().peekable(),
+        }
+        .parse()
+        .map_err(|reason| ContactError::InvalidEmail {
+            email: email.to_string(),
+            reason,
+        })
+    }
+}
+
+/// A parser for `addr-spec = local-part "@" domain`, without the whitespace and comments
+/// RFC 822 allows between tokens.
+struct AddrSpecParser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl AddrSpecParser<'_> {
+    fn parse(mut self) -> Result<(), String> {
+        // local-part = word *("." word)
+        loop {
+            match self.chars.peek() {
+                Some('"') => self.delimited('"', '"', "quoted string")?,
+                _ => self.atom("the local part")?,
+            }
+            if self.chars.next_if_eq(&'.').is_none() {
+                break;
+            }
+        }
+        match self.chars.next() {
+            Some('@') => {}
+            Some(c) => return Err(format!("unexpected character `{c}` in the local part")),
+            None => return Err("missing `@`".to_string()),
+        }
+        // domain = sub-domain *("." sub-domain)
+        loop {
+            match self.chars.peek() {
+                Some('[') => self.delimited('[', ']', "domain literal")?,
+                _ => self.atom("the domain")?,
+            }
+            if self.chars.next_if_eq(&'.').is_none() {
+                break;
+            }
+        }
+        match self.chars.next() {
+            Some(c) => Err(format!("unexpected character `{c}` in the domain")),
+            None => Ok(()),
+        }
+    }
+
+    /// Consume a non-empty run of characters that are neither specials, space nor controls.
+    fn atom(&mut self, part: &str) -> Result<(), String> {
+        let mut empty = true;
+        while self
+            .chars
+            .next_if(|&c| !c.is_control() && c != ' ' && !"()<>@,;:\\\".[]".contains(c))
+            .is_some()
+        {
+            empty = false;
+        }
+        if empty {
+            return Err(match self.chars.peek() {
+                Some(c) => format!("unexpected character `{c}` in {part}"),
+                None => format!("{part} is empty or ends with `.`"),
+            });
+        }
+        Ok(())
+    }
+
+    /// Consume a quoted string or domain literal, where `\` escapes the next character.
+    fn delimited(&mut self, open: char, close: char, what: &str) -> Result<(), String> {
+        self.chars.next_if_eq(&open);
+        while let Some(c) = self.chars.next() {
+            match c {
+                c if c == close => return Ok(()),
+                '\\' => {
+                    self.chars.next();
+                }
+                '\r' => return Err(format!("carriage return in {what}")),
+                c if c == open => return Err(format!("unexpected `{c}` in {what}")),
+                _ => {}
+            }
+        }
+        Err(format!("unterminated {what}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{Contact, PyProjectToml};
+
+    fn email(email: &str) -> Contact {
+        Contact::Email {
+            email: email.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        for valid in [
+            "john.doe@example.net",
+            "\"john doe\"@example.net",
+            "\"john\\\"doe\"@example.net",
+            "john+spam@[127.0.0.1]",
+        ] {
+            email(valid).validate().unwrap();
+        }
+        Contact::Name {
+            name: "John Doe".to_string(),
+        }
+        .validate()
+        .unwrap();
+
+        let error = |email_address: &str| email(email_address).validate().unwrap_err().to_string();
+        assert_eq!(error("1"), "`1` is not a valid email address: missing `@`");
+        assert_eq!(
+            error("foo@"),
+            "`foo@` is not a valid email address: the domain is empty or ends with `.`"
+        );
+        assert_eq!(
+            error("not an email"),
+            "`not an email` is not a valid email address: unexpected character ` ` in the local part"
+        );
+        assert_eq!(
+            error("\"john@example.net"),
+            "`\"john@example.net` is not a valid email address: unterminated quoted string"
+        );
+    }
+
+    #[test]
+    fn test_validate_empty() {
+        let source = r#"[project]
+name = "spam"
+authors = [
+  {name = ""},
+  {name = "", email = ""},
+  {name = "John Doe", email = " "},
+]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let errors: Vec<String> = project_toml
+            .project
+            .unwrap()
+            .authors
+            .unwrap()
+            .iter()
+            .map(|author| author.validate().unwrap_err().to_string())
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                "The name of a contact must not be empty",
+                "A contact needs a name or an email, but both are empty",
+                "The email of a contact must not be empty",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_display_from_str() {
+        for contact in [
+            Contact::NameEmail {
+                name: "John Doe".to_string(),
+                email: "john.doe@example.net".to_string(),
+            },
+            Contact::Name {
+                name: "John Doe".to_string(),
+            },
+            email("john.doe@example.net"),
+            Contact::NameEmail {
+                // Ensure that combining characters survive the round trip
+                name: "Z͑ͫ̓ͪ̂ͫ̽͏̴̙̤̞͉͚̯̞̠͍A̴̵̜̰͔ͫ͗͢L̠ͨͧͩ͘G̴̻͈͍͔̹̑͗̎̅͛́Ǫ̵̹̻̝̳͂̌̌͘".to_string(),
+                email: "zalgo@example.net".to_string(),
+            },
+        ] {
+            assert_eq!(Contact::from_str(&contact.to_string()).unwrap(), contact);
+        }
+        assert_eq!(
+            email("john.doe@example.net").to_string(),
+            "<john.doe@example.net>"
+        );
+
+        for invalid in [
+            "",
+            "  ",
+            "John Doe <>",
+            "<john@example.net",
+            "John <Doe> <x@y.z>",
+        ] {
+            assert_eq!(
+                Contact::from_str(invalid).unwrap_err().to_string(),
+                format!("`{invalid}` is not a valid contact, expected `Name <email>`, `Name` or `<email>`")
+            );
+        }
+    }
+}
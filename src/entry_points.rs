@@ -0,0 +1,135 @@
+//! Validation of `project.scripts`, `project.gui-scripts` and `project.entry-points`.
+
+use thiserror::Error;
+
+use crate::Project;
+
+/// An invalid entry point, reported by [`Project::validate_entry_points`].
+///
+/// `group` is the entry point group in the core metadata, i.e. `console_scripts` for
+/// `project.scripts` and `gui_scripts` for `project.gui-scripts`.
+#[derive(Debug, Error)]
+pub enum EntryPointError {
+    #[error("`{group}` is not a valid entry point group name")]
+    InvalidGroup { group: String },
+    #[error("`{name}` in the entry point group `{group}` is not a valid entry point name")]
+    InvalidName { group: String, name: String },
+    #[error("`{name}` in the entry point group `{group}` has the invalid object reference `{value}`, expected `module:object`")]
+    InvalidObjectReference {
+        group: String,
+        name: String,
+        value: String,
+    },
+}
+
+/// Whether `name` is a Python identifier, approximating `str.isidentifier()`.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map_or(false, |c| c == '_' || c.is_alphabetic())
+        && chars.all(|c| c == '_' || c.is_alphanumeric())
+}
+
+/// Whether `value` is a dotted path of identifiers, such as `spam.cli`.
+fn is_dotted_path(value: &str) -> bool {
+    value.split('.').all(is_identifier)
+}
+
+/// Whether `value` is an object reference `module(.sub)*(:object(.attr)*)?`.
+fn is_object_reference(value: &str) -> bool {
+    let (module, object) = match value.split_once(':') {
+        Some((module, object)) => (module.trim(), Some(object.trim())),
+        None => (value.trim(), None),
+    };
+    is_dotted_path(module) && object.map_or(true, is_dotted_path)
+}
+
+impl Project {
+    /// Check the entry points in `scripts`, `gui-scripts` and `entry-points` against the entry
+    /// points specification.
+    ///
+    /// Values must be object references like `spam.cli:main`, names must not contain `=` or start
+    /// with `[` or surrounding whitespace, and group names must be dotted names like
+    /// `pytest11`.
+    pub fn validate_entry_points(&self) -> Result<(), EntryPointError> {
+        let scripts = [
+            ("console_scripts", &self.scripts),
+            ("gui_scripts", &self.gui_scripts),
+        ]
+        .into_iter()
+        .filter_map(|(group, entries)| Some((group, entries.as_ref()?)));
+        let entry_points = self
+            .entry_points
+            .iter()
+            .flatten()
+            .map(|(group, entries)| (group.as_str(), entries));
+        for (group, entries) in scripts.chain(entry_points) {
+            if !is_dotted_path(group) {
+                return Err(EntryPointError::InvalidGroup {
+                    group: group.to_string(),
+                });
+            }
+            for (name, value) in entries {
+                if name.is_empty()
+                    || name.contains('=')
+                    || name.starts_with('[')
+                    || name.trim() != name
+                {
+                    return Err(EntryPointError::InvalidName {
+                        group: group.to_string(),
+                        name: name.clone(),
+                    });
+                }
+                if !is_object_reference(value) {
+                    return Err(EntryPointError::InvalidObjectReference {
+                        group: group.to_string(),
+                        name: name.clone(),
+                        value: value.clone(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use crate::Project;
+
+    #[test]
+    fn test_validate_entry_points() {
+        let mut project = Project::new("spam".to_string());
+        project.scripts = Some(IndexMap::from([(
+            "spam-cli".to_string(),
+            "spam:main_cli".to_string(),
+        )]));
+        project.entry_points = Some(IndexMap::from([(
+            "spam.magical".to_string(),
+            IndexMap::from([("tomatoes".to_string(), "spam.module".to_string())]),
+        )]));
+        project.validate_entry_points().unwrap();
+
+        project.gui_scripts = Some(IndexMap::from([(
+            "spam-gui".to_string(),
+            "spam:".to_string(),
+        )]));
+        assert_eq!(
+            project.validate_entry_points().unwrap_err().to_string(),
+            "`spam-gui` in the entry point group `gui_scripts` has the invalid object reference `spam:`, expected `module:object`"
+        );
+
+        project.gui_scripts = None;
+        project.entry_points = Some(IndexMap::from([(
+            "spam magical".to_string(),
+            IndexMap::new(),
+        )]));
+        assert_eq!(
+            project.validate_entry_points().unwrap_err().to_string(),
+            "`spam magical` is not a valid entry point group name"
+        );
+    }
+}
@@ -2,16 +2,66 @@
 mod pep639_glob;
 
 #[cfg(feature = "pep639-glob")]
-pub use pep639_glob::{parse_pep639_glob, Pep639GlobError};
+pub use pep639_glob::{
+    normalize_license_path, parse_pep639_glob, resolve_license_files, Pep639GlobError,
+};
 
+#[cfg(feature = "preserve-formatting")]
+mod verbatim;
+
+#[cfg(feature = "preserve-formatting")]
+pub use verbatim::{VerbatimBuildSystem, VerbatimError, VerbatimRequirement};
+
+#[cfg(feature = "spdx")]
+mod license_expression;
+
+#[cfg(feature = "spdx")]
+pub use license_expression::SpdxError;
+
+#[cfg(feature = "requirements-txt")]
+mod requirements_txt;
+
+#[cfg(feature = "json")]
+mod canonical_json;
+#[cfg(feature = "classifiers")]
+mod classifiers;
+mod dot;
+mod dynamic;
+mod email;
+mod entry_points;
+mod markers;
+mod merge;
+mod metadata;
+mod names;
 pub mod pep735_resolve;
+mod resolution;
+mod spanned;
+mod style;
+
+pub use dynamic::{DynamicError, DynamicField, VersionStatus};
+pub use email::ContactError;
+pub use entry_points::EntryPointError;
+pub use metadata::MetadataError;
+pub use names::{GroupNameError, NameWarning};
+pub use resolution::{
+    Cycle, InstallTarget, OptionalDependencies, ResolveError, ResolveErrorKind, ResolveOptions,
+    ResolvedDependencies, ResolvedFull, SortedResolvedDependencies,
+};
+pub use spanned::{SpannedBuildSystem, SpannedProject, SpannedPyProjectToml};
+pub use style::StyleLint;
 
 use indexmap::IndexMap;
-use pep440_rs::{Version, VersionSpecifiers};
-use pep508_rs::Requirement;
+use pep440_rs::{Operator, Version, VersionSpecifiers};
+use pep508_rs::{ExtraName, InvalidNameError, PackageName, Requirement, VersionOrUrl};
+use resolution::normalize_name;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::ops::Deref;
-use std::path::PathBuf;
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::ops::{Deref, Range};
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+use thiserror::Error;
 
 /// The `[build-system]` section of a pyproject.toml as specified in PEP 517
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -25,8 +75,153 @@ pub struct BuildSystem {
     pub backend_path: Option<Vec<String>>,
 }
 
+impl BuildSystem {
+    /// The backend PEP 517 frontends fall back to without `build-backend`
+    const LEGACY_BACKEND: &'static str = "setuptools.build_meta:__legacy__";
+    /// The build requirements PEP 518 frontends fall back to without `requires`
+    const LEGACY_REQUIRES: [&'static str; 2] = ["setuptools", "wheel"];
+
+    /// The `build-backend`, or the legacy setuptools backend `setuptools.build_meta:__legacy__`
+    /// that PEP 517 frontends use without one.
+    pub fn effective_backend(&self) -> &str {
+        self.build_backend
+            .as_deref()
+            .unwrap_or(Self::LEGACY_BACKEND)
+    }
+
+    /// The build requirements, or `setuptools` and `wheel` as PEP 518 frontends assume if
+    /// `requires` is empty.
+    pub fn effective_requires(&self) -> Vec<Requirement> {
+        if !self.requires.is_empty() {
+            return self.requires.clone();
+        }
+        Self::LEGACY_REQUIRES
+            .iter()
+            .map(|requirement| Requirement::from_str(requirement).expect("valid requirement"))
+            .collect()
+    }
+
+    /// If `build-backend` is unset but `requires` contains a well-known build backend, return the
+    /// `build-backend` value for it.
+    ///
+    /// Without `build-backend`, frontends fall back to the legacy setuptools backend, so e.g.
+    /// `requires = ["hatchling"]` alone doesn't build with hatchling. setuptools itself is not
+    /// reported since the fallback already uses it.
+    pub fn likely_missing_backend(&self) -> Option<String> {
+        if self.build_backend.is_some() {
+            return None;
+        }
+        self.requires.iter().find_map(|requirement| {
+            let backend = match requirement.name.as_ref() {
+                "flit-core" => "flit_core.buildapi",
+                "hatchling" => "hatchling.build",
+                "maturin" => "maturin",
+                "meson-python" => "mesonpy",
+                "pdm-backend" => "pdm.backend",
+                "poetry-core" => "poetry.core.masonry.api",
+                "scikit-build-core" => "scikit_build_core.build",
+                "uv-build" => "uv_build",
+                _ => return None,
+            };
+            Some(backend.to_string())
+        })
+    }
+
+    /// Check that the `backend-path` entries are relative paths that stay within the project
+    /// root, as PEP 517 requires.
+    ///
+    /// The check is lexical, it does not access the filesystem or follow symlinks.
+    pub fn validate_backend_path(&self) -> Result<(), BackendPathError> {
+        for entry in self.backend_path.iter().flatten() {
+            let path = Path::new(entry);
+            if path.has_root() || path.is_absolute() {
+                return Err(BackendPathError::Absolute(entry.clone()));
+            }
+            let mut depth = 0usize;
+            for component in path.components() {
+                match component {
+                    Component::Normal(_) => depth += 1,
+                    Component::CurDir => {}
+                    Component::ParentDir => {
+                        depth = depth
+                            .checked_sub(1)
+                            .ok_or_else(|| BackendPathError::OutsideProject(entry.clone()))?;
+                    }
+                    Component::RootDir | Component::Prefix(_) => {
+                        return Err(BackendPathError::Absolute(entry.clone()));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An invalid `build-system.backend-path` entry, reported by [`BuildSystem::validate_backend_path`]
+#[derive(Debug, Error)]
+pub enum BackendPathError {
+    #[error("The `build-system.backend-path` entry `{0}` must be a relative path")]
+    Absolute(String),
+    #[error("The `build-system.backend-path` entry `{0}` points outside the project root")]
+    OutsideProject(String),
+}
+
+/// Where in a pyproject.toml a requirement is declared
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum RequirementSource {
+    /// `build-system.requires`
+    BuildSystem,
+    /// `project.dependencies`
+    Dependencies,
+    /// An extra in `project.optional-dependencies`
+    OptionalDependencies(String),
+    /// A group in `dependency-groups`
+    DependencyGroup(String),
+}
+
+/// Display the source as a TOML path, e.g., `project.optional-dependencies.test`.
+impl Display for RequirementSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequirementSource::BuildSystem => write!(f, "build-system.requires"),
+            RequirementSource::Dependencies => write!(f, "project.dependencies"),
+            RequirementSource::OptionalDependencies(extra) => {
+                write!(f, "project.optional-dependencies.{extra}")
+            }
+            RequirementSource::DependencyGroup(group) => write!(f, "dependency-groups.{group}"),
+        }
+    }
+}
+
+/// An error reading a `pyproject.toml` file, returned by [`PyProjectToml::from_path`]
+#[derive(Debug, Error)]
+pub enum PyProjectTomlError {
+    #[error("Failed to read `{}`", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        err: std::io::Error,
+    },
+    #[error("Failed to parse `{}`", path.display())]
+    Toml {
+        path: PathBuf,
+        #[source]
+        err: toml::de::Error,
+    },
+}
+
+/// An error from [`PyProjectToml::new_strict`]
+#[derive(Debug, Error)]
+pub enum StrictError {
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+    /// A key that is not part of the specification, with the byte range of the key in the source
+    #[error("Unknown key `{key}`")]
+    UnknownKey { key: String, span: Range<usize> },
+}
+
 /// A pyproject.toml as specified in PEP 517
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub struct PyProjectToml {
     /// Build-related data
@@ -35,13 +230,61 @@ pub struct PyProjectToml {
     pub project: Option<Project>,
     /// Dependency groups table
     pub dependency_groups: Option<DependencyGroups>,
+    /// The `[tool]` table, with the configuration of each tool kept as is
+    pub tool: Option<IndexMap<String, toml::Value>>,
+}
+
+/// Builds a [`PyProjectToml`] section by section, leaving the sections that aren't set empty.
+#[derive(Debug, Clone, Default)]
+pub struct PyProjectTomlBuilder {
+    build_system: Option<BuildSystem>,
+    project: Option<Project>,
+    dependency_groups: Option<DependencyGroups>,
+    tool: Option<IndexMap<String, toml::Value>>,
+}
+
+impl PyProjectTomlBuilder {
+    /// Set the `[build-system]` table
+    pub fn build_system(mut self, build_system: BuildSystem) -> Self {
+        self.build_system = Some(build_system);
+        self
+    }
+
+    /// Set the `[project]` table
+    pub fn project(mut self, project: Project) -> Self {
+        self.project = Some(project);
+        self
+    }
+
+    /// Set the `[dependency-groups]` table
+    pub fn dependency_groups(mut self, dependency_groups: DependencyGroups) -> Self {
+        self.dependency_groups = Some(dependency_groups);
+        self
+    }
+
+    /// Set the `[tool]` table
+    pub fn tool(mut self, tool: IndexMap<String, toml::Value>) -> Self {
+        self.tool = Some(tool);
+        self
+    }
+
+    /// Create the `PyProjectToml`
+    pub fn build(self) -> PyProjectToml {
+        PyProjectToml {
+            build_system: self.build_system,
+            project: self.project,
+            dependency_groups: self.dependency_groups,
+            tool: self.tool,
+        }
+    }
 }
 
 /// PEP 621 project metadata
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case")]
 pub struct Project {
-    /// The name of the project
+    /// The name of the project, as written. Use [`Project::normalized_name`] for the normalized
+    /// name.
     pub name: String,
     /// The version of the project as supported by PEP 440
     pub version: Option<Version>,
@@ -89,6 +332,28 @@ pub struct Project {
     pub dynamic: Option<Vec<String>>,
 }
 
+/// Whether `requires_python` allows any `X.Y.*` release of the Python version `minor`.
+///
+/// The releases allowed by the specifiers within `X.Y.*` form a range with holes from `!=`, so
+/// it is enough to try `X.Y.0`, the versions the specifiers mention and the release after each
+/// of them, e.g. 3.9.1 for `>3.9`.
+fn allows_minor(requires_python: &VersionSpecifiers, minor: &Version) -> bool {
+    let prefix = &minor.release()[..2];
+    let mentioned = requires_python
+        .iter()
+        .map(|specifier| specifier.version().release())
+        .filter(|release| release.len() >= 2 && &release[..2] == prefix)
+        .flat_map(|release| {
+            let mut next = release.to_vec();
+            next.resize(next.len().max(3), 0);
+            *next.last_mut().expect("has three segments") += 1;
+            [Version::new(release), Version::new(next)]
+        });
+    std::iter::once(Version::new(prefix))
+        .chain(mentioned)
+        .any(|candidate| requires_python.contains(&candidate))
+}
+
 impl Project {
     /// Initializes the only field mandatory in PEP 621 (`name`) and leaves everything else empty
     pub fn new(name: String) -> Self {
@@ -113,6 +378,295 @@ impl Project {
             dynamic: None,
         }
     }
+
+    /// The project name normalized as specified in PEP 503, e.g. `spam-eggs` for `Spam_Eggs`.
+    ///
+    /// Self-references such as `spam-eggs[test]` are matched against this name.
+    pub fn normalized_name(&self) -> Result<PackageName, InvalidNameError> {
+        PackageName::from_str(&self.name)
+    }
+
+    /// Returns, per entry point group, the entry point names that are defined more than once.
+    ///
+    /// Names are compared case-insensitively. `scripts` and `gui_scripts` are checked together with
+    /// the `console_scripts` and `gui_scripts` groups in `entry_points`, since they end up in the
+    /// same group in the core metadata. All spellings of a colliding name are reported, in
+    /// declaration order.
+    pub fn duplicate_entry_points(&self) -> IndexMap<String, Vec<String>> {
+        let mut groups: IndexMap<&str, Vec<&str>> = IndexMap::new();
+        for (group, entries) in [
+            ("console_scripts", &self.scripts),
+            ("gui_scripts", &self.gui_scripts),
+        ] {
+            if let Some(entries) = entries {
+                groups
+                    .entry(group)
+                    .or_default()
+                    .extend(entries.keys().map(String::as_str));
+            }
+        }
+        for (group, entries) in self.entry_points.iter().flatten() {
+            groups
+                .entry(group)
+                .or_default()
+                .extend(entries.keys().map(String::as_str));
+        }
+
+        let mut duplicates = IndexMap::new();
+        for (group, names) in groups {
+            let mut by_name: IndexMap<String, Vec<&str>> = IndexMap::new();
+            for name in names {
+                by_name.entry(name.to_lowercase()).or_default().push(name);
+            }
+            let colliding: Vec<String> = by_name
+                .into_values()
+                .filter(|names| names.len() > 1)
+                .flatten()
+                .map(ToString::to_string)
+                .collect();
+            if !colliding.is_empty() {
+                duplicates.insert(group.to_string(), colliding);
+            }
+        }
+        duplicates
+    }
+
+    /// Return the base dependencies that use the compatible release operator, e.g. `~=2.2`.
+    ///
+    /// A malformed compatible release such as `~=2`, which has only one version segment, is
+    /// already rejected by PEP 440 when parsing the requirement.
+    pub fn compatible_release_dependencies(&self) -> Vec<&Requirement> {
+        self.dependencies
+            .iter()
+            .flatten()
+            .filter(|requirement| {
+                let Some(VersionOrUrl::VersionSpecifier(specifiers)) = &requirement.version_or_url
+                else {
+                    return false;
+                };
+                specifiers
+                    .iter()
+                    .any(|specifier| *specifier.operator() == Operator::TildeEqual)
+            })
+            .collect()
+    }
+
+    /// Return the `License ::` classifiers, which PEP 639 deprecates in favor of an SPDX
+    /// expression in `license`.
+    pub fn license_classifiers(&self) -> Vec<&str> {
+        self.classifiers
+            .iter()
+            .flatten()
+            .map(String::as_str)
+            .filter(|classifier| classifier.starts_with("License ::"))
+            .collect()
+    }
+
+    /// Whether any of the classifiers is a deprecated `License ::` classifier.
+    pub fn has_license_classifiers(&self) -> bool {
+        !self.license_classifiers().is_empty()
+    }
+
+    /// Return the extras whose names normalize to the same name under PEP 685, such as `my-extra`
+    /// and `my_extra`, as pairs of the normalized name and the colliding names.
+    pub fn normalization_collisions(&self) -> Vec<(String, Vec<String>)> {
+        let mut by_name: IndexMap<String, Vec<String>> = IndexMap::new();
+        for extra in self
+            .optional_dependencies
+            .iter()
+            .flat_map(|extras| extras.keys())
+        {
+            by_name
+                .entry(normalize_name(extra))
+                .or_default()
+                .push(extra.clone());
+        }
+        by_name
+            .into_iter()
+            .filter(|(_, extras)| extras.len() > 1)
+            .collect()
+    }
+
+    /// Return the optional dependencies keyed by their PEP 685 normalized extra names, e.g.
+    /// `my-extra` for `My_Extra`.
+    ///
+    /// Returns an error if an extra name is invalid, or if two extras normalize to the same name,
+    /// see [`Project::normalization_collisions`] for listing all of them.
+    pub fn normalized_optional_dependencies(
+        &self,
+    ) -> Result<IndexMap<ExtraName, &Vec<Requirement>>, ExtraNameError> {
+        let mut extras: IndexMap<ExtraName, (&str, &Vec<Requirement>)> = IndexMap::new();
+        for (extra, requirements) in self
+            .optional_dependencies
+            .iter()
+            .flat_map(|extras| extras.iter())
+        {
+            if let Some((first, _)) =
+                extras.insert(ExtraName::from_str(extra)?, (extra, requirements))
+            {
+                return Err(ExtraNameError::Collision {
+                    first: first.to_string(),
+                    second: extra.clone(),
+                });
+            }
+        }
+        Ok(extras
+            .into_iter()
+            .map(|(name, (_, requirements))| (name, requirements))
+            .collect())
+    }
+
+    /// Filter interpreters, given as implementation name and Python version, to those allowed by
+    /// `requires-python`. All interpreters are allowed if `requires-python` is unset.
+    pub fn compatible_interpreters(
+        &self,
+        interpreters: &[(String, Version)],
+    ) -> Vec<(String, Version)> {
+        interpreters
+            .iter()
+            .filter(|(_, version)| self.supports_python(version).unwrap_or(true))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether `requires-python` allows the Python version, or `None` if `requires-python` is
+    /// unset.
+    ///
+    /// Pre-releases follow the PEP 440 rules, e.g. `3.13.0rc1` is not allowed by `>=3.13`.
+    pub fn supports_python(&self, version: &Version) -> Option<bool> {
+        self.requires_python
+            .as_ref()
+            .map(|requires_python| requires_python.contains(version))
+    }
+
+    /// Return the Python versions of `Programming Language :: Python :: X.Y` classifiers that
+    /// `requires-python` excludes, e.g. 3.7 with `requires-python = ">=3.9"`.
+    ///
+    /// A classifier only conflicts if no `X.Y.*` release is allowed, so `3.8` is fine with
+    /// `>=3.8.1` and `3.9` with `>3.9`. Major-only classifiers such as
+    /// `Programming Language :: Python :: 3` are skipped.
+    pub fn classifier_python_conflicts(&self) -> Vec<Version> {
+        let Some(requires_python) = &self.requires_python else {
+            return Vec::new();
+        };
+        self.classifiers
+            .iter()
+            .flatten()
+            .filter_map(|classifier| classifier.strip_prefix("Programming Language :: Python :: "))
+            .filter(|version| version.split('.').count() == 2)
+            .filter_map(|version| Version::from_str(version).ok())
+            .filter(|version| !allows_minor(requires_python, version))
+            .collect()
+    }
+
+    /// Return the most prominent project URL, e.g. to render a single link.
+    ///
+    /// The `urls` labels are compared case-insensitively and ignoring spaces, preferring
+    /// `homepage`, then `home-page`, then `repository`.
+    pub fn homepage(&self) -> Option<&str> {
+        let urls = self.urls.as_ref()?;
+        let find = |wanted: &str| {
+            urls.iter()
+                .find(|(label, _)| {
+                    label
+                        .chars()
+                        .filter(|c| *c != ' ')
+                        .flat_map(char::to_lowercase)
+                        .eq(wanted.chars())
+                })
+                .map(|(_, url)| url.as_str())
+        };
+        find("homepage")
+            .or_else(|| find("home-page"))
+            .or_else(|| find("repository"))
+    }
+
+    /// Check that no two `urls` labels collide after PyPI's normalization, which lowercases
+    /// labels and removes spaces and hyphens, e.g. `Home-page` and `home page`.
+    pub fn validate_urls(&self) -> Result<(), UrlError> {
+        let normalize = |label: &str| -> String {
+            label
+                .chars()
+                .filter(|c| !matches!(c, ' ' | '-'))
+                .flat_map(char::to_lowercase)
+                .collect()
+        };
+        let mut seen: IndexMap<String, &str> = IndexMap::new();
+        for label in self.urls.iter().flat_map(|urls| urls.keys()) {
+            if let Some(first) = seen.insert(normalize(label), label) {
+                return Err(UrlError::LabelCollision {
+                    first: first.to_string(),
+                    second: label.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the project declares any base or optional dependencies.
+    ///
+    /// A project whose `dependencies` or `optional-dependencies` are listed in `dynamic` may get
+    /// dependencies from the build backend, so it counts as having dependencies. Build
+    /// requirements and dependency groups are not part of the `[project]` table and are not
+    /// considered.
+    pub fn has_any_dependencies(&self) -> bool {
+        self.dependencies
+            .as_ref()
+            .map_or(false, |dependencies| !dependencies.is_empty())
+            || self
+                .optional_dependencies
+                .iter()
+                .flat_map(|extras| extras.values())
+                .any(|requirements| !requirements.is_empty())
+            || self.is_dynamic("dependencies")
+            || self.is_dynamic("optional-dependencies")
+    }
+
+    /// Return the license file patterns from the deprecated `license = { file = "..." }` table and
+    /// from `license-files`, in that order, e.g. to glob them in one pass.
+    ///
+    /// The `file` path is returned as written, it is a plain path and not a glob. Paths that are
+    /// not valid UTF-8 are skipped.
+    pub fn license_file_patterns(&self) -> Vec<&str> {
+        let file = match &self.license {
+            Some(License::File { file }) => file.to_str(),
+            _ => None,
+        };
+        file.into_iter()
+            .chain(self.license_files.iter().flatten().map(String::as_str))
+            .collect()
+    }
+
+    /// Whether `project.license` uses one of the tables deprecated by PEP 639, see
+    /// [`License::is_deprecated`].
+    pub fn uses_legacy_license(&self) -> bool {
+        self.license.as_ref().map_or(false, License::is_deprecated)
+    }
+
+    /// Iterate over the base and optional dependencies together with a label for where they are
+    /// declared, without resolving self-references, e.g. to lint for pinned versions.
+    ///
+    /// The label is `dependencies` for the base dependencies and `optional:<extra>` for an extra.
+    /// It is owned, since the extra is part of it. Dependency groups and build requirements are
+    /// not part of the `[project]` table, see [`PyProjectToml::requirements_with_source`] for
+    /// iterating over them too.
+    pub fn iter_requirements(&self) -> impl Iterator<Item = (String, &Requirement)> {
+        let dependencies = self
+            .dependencies
+            .iter()
+            .flatten()
+            .map(|requirement| ("dependencies".to_string(), requirement));
+        let optional_dependencies = self
+            .optional_dependencies
+            .iter()
+            .flat_map(|extras| extras.iter())
+            .flat_map(|(extra, requirements)| {
+                requirements
+                    .iter()
+                    .map(move |requirement| (format!("optional:{extra}"), requirement))
+            });
+        dependencies.chain(optional_dependencies)
+    }
 }
 
 /// The full description of the project (i.e. the README).
@@ -134,6 +688,90 @@ pub enum ReadMe {
     },
 }
 
+/// An invalid `project.readme` table, reported by [`ReadMe::validate`]
+#[derive(Debug, Error)]
+pub enum ReadMeError {
+    #[error("`project.readme` must set only one of `file` and `text`, not both")]
+    BothFileAndText,
+    #[error("`project.readme` must set one of `file` and `text`")]
+    NeitherFileNorText,
+    #[error("`project.readme` sets `text`, so it must also set `content-type`")]
+    TextWithoutContentType,
+}
+
+impl ReadMe {
+    /// Check that a readme table sets exactly one of `file` and `text`, and that `text` comes
+    /// with a `content-type`.
+    pub fn validate(&self) -> Result<(), ReadMeError> {
+        match self {
+            ReadMe::RelativePath(_) => Ok(()),
+            ReadMe::Table {
+                file: Some(_),
+                text: Some(_),
+                ..
+            } => Err(ReadMeError::BothFileAndText),
+            ReadMe::Table {
+                file: None,
+                text: None,
+                ..
+            } => Err(ReadMeError::NeitherFileNorText),
+            ReadMe::Table {
+                text: Some(_),
+                content_type: None,
+                ..
+            } => Err(ReadMeError::TextWithoutContentType),
+            ReadMe::Table { .. } => Ok(()),
+        }
+    }
+
+    /// The media type of [`ReadMe::content_type`] without parameters, e.g. `text/markdown` for
+    /// `text/markdown; charset=UTF-8`.
+    pub fn content_type_base(&self) -> Option<&str> {
+        let content_type = self.content_type()?;
+        let base = content_type.split(';').next().unwrap_or_default().trim();
+        (!base.is_empty()).then_some(base)
+    }
+
+    /// The `charset` parameter of [`ReadMe::content_type`], e.g. `UTF-8` for
+    /// `text/markdown; charset=UTF-8`.
+    pub fn content_type_charset(&self) -> Option<&str> {
+        self.content_type()?
+            .split(';')
+            .skip(1)
+            .filter_map(|parameter| parameter.split_once('='))
+            .find(|(key, _)| key.trim().eq_ignore_ascii_case("charset"))
+            .map(|(_, value)| value.trim().trim_matches('"'))
+    }
+
+    /// The content type of the full description: the `content-type` if given, otherwise
+    /// inferred from the file extension.
+    ///
+    /// The inferred types are `text/markdown` for `.md`, `text/x-rst` for `.rst` and `text/plain`
+    /// for `.txt`. Returns `None` for other extensions.
+    pub fn content_type(&self) -> Option<&str> {
+        let file = match self {
+            ReadMe::RelativePath(file) => file,
+            ReadMe::Table {
+                content_type: Some(content_type),
+                ..
+            } => return Some(content_type),
+            ReadMe::Table {
+                file: Some(file), ..
+            } => file,
+            ReadMe::Table { .. } => return None,
+        };
+        let extension = Path::new(file).extension()?.to_str()?;
+        [
+            ("md", "text/markdown"),
+            ("rst", "text/x-rst"),
+            ("txt", "text/plain"),
+        ]
+        .into_iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(extension))
+        .map(|(_, content_type)| content_type)
+    }
+}
+
 /// The optional `project.license` key
 ///
 /// Specified in <https://packaging.python.org/en/latest/specifications/pyproject-toml/#license>.
@@ -156,6 +794,51 @@ pub enum License {
     },
 }
 
+impl License {
+    /// Whether this is one of the `{ text = "..." }` and `{ file = "..." }` tables that PEP 639
+    /// deprecates in favor of an SPDX expression.
+    pub fn is_deprecated(&self) -> bool {
+        !matches!(self, License::Spdx(_))
+    }
+
+    /// The SPDX expression to write instead, e.g. when migrating to PEP 639.
+    ///
+    /// For the deprecated tables, no expression can be derived and `None` is returned; the
+    /// license classifiers may help instead, see [`Project::license_classifiers`].
+    pub fn to_spdx_hint(&self) -> Option<String> {
+        match self {
+            License::Spdx(expression) => Some(expression.clone()),
+            License::Text { .. } | License::File { .. } => None,
+        }
+    }
+}
+
+/// A mistake in the `project.license` key, reported by [`PyProjectToml::validate_license`]
+#[derive(Debug, Error)]
+pub enum LicenseError {
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+    #[error("`project.license` must be a single SPDX expression, not a list, try `{suggestion}`")]
+    ListNotAllowed { suggestion: String },
+}
+
+/// A mistake in the `[project.urls]` table, reported by [`Project::validate_urls`]
+#[derive(Debug, Error)]
+pub enum UrlError {
+    #[error("The `project.urls` labels `{first}` and `{second}` are the same label for PyPI")]
+    LabelCollision { first: String, second: String },
+}
+
+/// A mistake in the `[project.optional-dependencies]` table, reported by
+/// [`Project::normalized_optional_dependencies`]
+#[derive(Debug, Error)]
+pub enum ExtraNameError {
+    #[error(transparent)]
+    InvalidName(#[from] InvalidNameError),
+    #[error("The extras `{first}` and `{second}` are the same extra after PEP 685 normalization")]
+    Collision { first: String, second: String },
+}
+
 /// A `project.authors` or `project.maintainers` entry.
 ///
 /// Specified in
@@ -171,12 +854,18 @@ pub enum License {
     expecting = "a table with 'name' and/or 'email' keys"
 )]
 pub enum Contact {
-    /// TODO(konsti): RFC 822 validation.
-    NameEmail { name: String, email: String },
-    /// TODO(konsti): RFC 822 validation.
-    Name { name: String },
-    /// TODO(konsti): RFC 822 validation.
-    Email { email: String },
+    /// See [`Contact::validate`] for RFC 822 validation.
+    NameEmail {
+        name: String,
+        email: String,
+    },
+    Name {
+        name: String,
+    },
+    /// See [`Contact::validate`] for RFC 822 validation.
+    Email {
+        email: String,
+    },
 }
 
 impl Contact {
@@ -211,9 +900,12 @@ impl Deref for DependencyGroups {
 }
 
 /// A specifier item in a Dependency Group
+///
+/// Features can add variants for entries beyond PEP 735, so the enum is non-exhaustive.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "kebab-case", untagged)]
 #[allow(clippy::large_enum_variant)]
+#[non_exhaustive]
 pub enum DependencyGroupSpecifier {
     /// PEP 508 requirement string
     String(Requirement),
@@ -223,6 +915,38 @@ pub enum DependencyGroupSpecifier {
         /// The name of the group to include
         include_group: String,
     },
+    /// Include the requirements from a `requirements.txt`-style file, relative to the project
+    /// root, see [`PyProjectToml::resolve_with_root`]
+    #[cfg(feature = "requirements-txt")]
+    Path {
+        /// The path of the requirements file
+        path: String,
+    },
+    /// Include an extra of the project, like the self-reference `spam[test]` in the project `spam`
+    #[cfg(feature = "include-extra")]
+    #[serde(rename_all = "kebab-case")]
+    IncludeExtra {
+        /// The name of the extra to include
+        include_extra: String,
+    },
+}
+
+impl DependencyGroupSpecifier {
+    /// Returns the requirement, if this is a PEP 508 requirement string.
+    pub fn as_requirement(&self) -> Option<&Requirement> {
+        match self {
+            DependencyGroupSpecifier::String(requirement) => Some(requirement),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the included group, if this is an `include-group` table.
+    pub fn as_include(&self) -> Option<&str> {
+        match self {
+            DependencyGroupSpecifier::Table { include_group } => Some(include_group),
+            _ => None,
+        }
+    }
 }
 
 impl PyProjectToml {
@@ -230,13 +954,375 @@ impl PyProjectToml {
     pub fn new(content: &str) -> Result<Self, toml::de::Error> {
         toml::de::from_str(content)
     }
+
+    /// Deserialize an already parsed `pyproject.toml`, e.g. one merged from multiple sources,
+    /// without rendering it to a string first
+    pub fn from_value(value: toml::Value) -> Result<Self, toml::de::Error> {
+        value.try_into()
+    }
+
+    /// Read and parse a `pyproject.toml` file, with the path in the error message.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, PyProjectTomlError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|err| PyProjectTomlError::Io {
+            path: path.to_path_buf(),
+            err,
+        })?;
+        Self::new(&content).map_err(|err| PyProjectTomlError::Toml {
+            path: path.to_path_buf(),
+            err,
+        })
+    }
+
+    /// Start building a `PyProjectToml` programmatically
+    pub fn builder() -> PyProjectTomlBuilder {
+        PyProjectTomlBuilder::default()
+    }
+
+    /// Make the defaults that tools apply to missing fields explicit.
+    ///
+    /// The defaults are:
+    ///
+    /// * A missing `[build-system]` becomes the PEP 518 and PEP 517 fallback,
+    ///   `requires = ["setuptools", "wheel"]` with the `setuptools.build_meta:__legacy__` backend,
+    ///   see [`BuildSystem::effective_backend`] and [`BuildSystem::effective_requires`].
+    /// * A missing `project.license-files` becomes `["LICEN[CS]E*", "COPYING*", "NOTICE*",
+    ///   "AUTHORS*"]`. These globs are not mandated by a PEP, they are the convention build
+    ///   backends adopted from the PEP 639 draft. They are only set if `project.license` is
+    ///   missing or an SPDX expression and `license-files` is not listed in `project.dynamic`,
+    ///   since a legacy `{ file = "..." }` or `{ text = "..." }` table must not be combined with
+    ///   `license-files`. Without a `[project]` table, there is nothing to set.
+    ///
+    /// Fields that are set, including `build-system` tables without `build-backend`, are kept.
+    pub fn apply_defaults(&mut self) {
+        if self.build_system.is_none() {
+            let legacy = BuildSystem {
+                requires: Vec::new(),
+                build_backend: None,
+                backend_path: None,
+            };
+            self.build_system = Some(BuildSystem {
+                requires: legacy.effective_requires(),
+                build_backend: Some(legacy.effective_backend().to_string()),
+                backend_path: None,
+            });
+        }
+        if let Some(project) = &mut self.project {
+            let spdx_or_missing = matches!(project.license, None | Some(License::Spdx(_)));
+            if project.license_files.is_none()
+                && spdx_or_missing
+                && !project.is_dynamic("license-files")
+            {
+                project.license_files = Some(
+                    ["LICEN[CS]E*", "COPYING*", "NOTICE*", "AUTHORS*"]
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect(),
+                );
+            }
+        }
+    }
+
+    /// Deserialize the `[tool.<name>]` table, e.g. `[tool.maturin]`, into a tool-specific type.
+    ///
+    /// Returns `None` if there is no such table.
+    pub fn tool_table<T: DeserializeOwned>(
+        &self,
+        name: &str,
+    ) -> Option<Result<T, toml::de::Error>> {
+        let table = self.tool.as_ref()?.get(name)?;
+        Some(table.clone().try_into())
+    }
+
+    /// Split into the build system, project and dependency groups tables without cloning
+    pub fn into_parts(
+        self,
+    ) -> (
+        Option<BuildSystem>,
+        Option<Project>,
+        Option<DependencyGroups>,
+    ) {
+        (self.build_system, self.project, self.dependency_groups)
+    }
+
+    /// Check `project.license` in `pyproject.toml` content, explaining common mistakes.
+    ///
+    /// [`PyProjectToml::new`] rejects an invalid license with a generic error. This instead
+    /// inspects the raw TOML value, and for a list of licenses such as `["MIT", "Apache-2.0"]`
+    /// returns [`LicenseError::ListNotAllowed`] suggesting the SPDX expression `MIT OR Apache-2.0`.
+    pub fn validate_license(content: &str) -> Result<(), LicenseError> {
+        let table: toml::Table = toml::de::from_str(content)?;
+        let Some(license) = table
+            .get("project")
+            .and_then(|project| project.get("license"))
+        else {
+            return Ok(());
+        };
+        match license.clone().try_into::<License>() {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                if let Some(licenses) = license.as_array() {
+                    let licenses: Option<Vec<&str>> =
+                        licenses.iter().map(toml::Value::as_str).collect();
+                    if let Some(licenses) = licenses {
+                        return Err(LicenseError::ListNotAllowed {
+                            suggestion: licenses.join(" OR "),
+                        });
+                    }
+                }
+                Err(err.into())
+            }
+        }
+    }
+
+    /// Iterate over the requirements in the build system, base dependencies, optional dependencies
+    /// and dependency groups together with where they are declared, without resolving any
+    /// references. `include-group` and `include-extra` entries are skipped.
+    pub fn requirements_with_source(
+        &self,
+    ) -> impl Iterator<Item = (RequirementSource, &Requirement)> {
+        let build_system = self
+            .build_system
+            .iter()
+            .flat_map(|build_system| build_system.requires.iter())
+            .map(|requirement| (RequirementSource::BuildSystem, requirement));
+        let project = self.project.iter().flat_map(|project| {
+            let dependencies = project
+                .dependencies
+                .iter()
+                .flatten()
+                .map(|requirement| (RequirementSource::Dependencies, requirement));
+            let optional_dependencies = project
+                .optional_dependencies
+                .iter()
+                .flat_map(|extras| extras.iter())
+                .flat_map(|(extra, requirements)| {
+                    requirements.iter().map(move |requirement| {
+                        (
+                            RequirementSource::OptionalDependencies(extra.clone()),
+                            requirement,
+                        )
+                    })
+                });
+            dependencies.chain(optional_dependencies)
+        });
+        let dependency_groups = self
+            .dependency_groups
+            .iter()
+            .flat_map(|groups| groups.iter())
+            .flat_map(|(group, specifiers)| {
+                specifiers
+                    .iter()
+                    .filter_map(move |specifier| match specifier {
+                        DependencyGroupSpecifier::String(requirement) => Some((
+                            RequirementSource::DependencyGroup(group.clone()),
+                            requirement,
+                        )),
+                        DependencyGroupSpecifier::Table { .. } => None,
+                        #[cfg(feature = "requirements-txt")]
+                        DependencyGroupSpecifier::Path { .. } => None,
+                        #[cfg(feature = "include-extra")]
+                        DependencyGroupSpecifier::IncludeExtra { .. } => None,
+                    })
+            });
+        build_system.chain(project).chain(dependency_groups)
+    }
+
+    /// Return the normalized names of all packages referenced in the build system, base
+    /// dependencies, optional dependencies and dependency groups, e.g. for an allowlist check.
+    pub fn referenced_packages(&self) -> BTreeSet<PackageName> {
+        self.requirements_with_source()
+            .map(|(_, requirement)| requirement.name.clone())
+            .collect()
+    }
+
+    /// Return the source, the normalized name and the version specifiers of every requirement,
+    /// e.g. for checking version constraints against a policy.
+    ///
+    /// Requirements without a version have empty specifiers, URL requirements are skipped.
+    pub fn requirement_specifiers(
+        &self,
+    ) -> Vec<(RequirementSource, PackageName, VersionSpecifiers)> {
+        self.requirements_with_source()
+            .filter_map(|(source, requirement)| {
+                let specifiers = match &requirement.version_or_url {
+                    None => VersionSpecifiers::empty(),
+                    Some(VersionOrUrl::VersionSpecifier(specifiers)) => specifiers.clone(),
+                    Some(VersionOrUrl::Url(_)) => return None,
+                };
+                Some((source, requirement.name.clone(), specifiers))
+            })
+            .collect()
+    }
+
+    /// Return the union of the extras requested on the package by any requirement, e.g. `a` and
+    /// `b` for `pytest[a]` in an extra and `pytest[b]` in a dependency group.
+    pub fn extras_requested_on(&self, package: &PackageName) -> BTreeSet<ExtraName> {
+        self.requirements_with_source()
+            .filter(|(_, requirement)| requirement.name == *package)
+            .flat_map(|(_, requirement)| requirement.extras.iter().cloned())
+            .collect()
+    }
+
+    /// Return every place where the package is required, e.g. to show what updating it affects.
+    pub fn locations_of(&self, package: &PackageName) -> Vec<RequirementSource> {
+        let mut locations = Vec::new();
+        for (source, requirement) in self.requirements_with_source() {
+            if requirement.name == *package && !locations.contains(&source) {
+                locations.push(source);
+            }
+        }
+        locations
+    }
+
+    /// Return the requirements on the project itself, such as `spam[test]` in the optional
+    /// dependencies of `spam`, i.e. the self-references that resolution expands.
+    ///
+    /// Returns an empty list if there is no `project.name` or it is not a valid package name.
+    pub fn self_references(&self) -> Vec<(RequirementSource, String)> {
+        let Some(name) = self
+            .project
+            .as_ref()
+            .and_then(|project| project.normalized_name().ok())
+        else {
+            return Vec::new();
+        };
+        self.requirements_with_source()
+            .filter(|(_, requirement)| requirement.name == name)
+            .map(|(source, requirement)| (source, requirement.to_string()))
+            .collect()
+    }
+
+    /// Return the names defined both as an extra and as a dependency group, compared after PEP 685
+    /// normalization, e.g. `dev`.
+    ///
+    /// Such names are allowed, but tools disagree on which of the two a name like `dev` refers
+    /// to, so this is meant for warnings. The names are normalized, in the order of the extras.
+    pub fn name_collisions(&self) -> Vec<String> {
+        let groups: BTreeSet<String> = self
+            .dependency_groups
+            .iter()
+            .flat_map(|groups| groups.keys())
+            .map(|group| normalize_name(group))
+            .collect();
+        let mut collisions = Vec::new();
+        for extra in self
+            .project
+            .iter()
+            .flat_map(|project| project.optional_dependencies.iter())
+            .flat_map(|extras| extras.keys())
+        {
+            let extra = normalize_name(extra);
+            if groups.contains(&extra) && !collisions.contains(&extra) {
+                collisions.push(extra);
+            }
+        }
+        collisions
+    }
+
+    /// Parse `pyproject.toml` content like [`PyProjectToml::new`], but fix common mistakes instead
+    /// of rejecting them, returning a warning for each fix.
+    ///
+    /// Currently, a numeric `project.version` such as `version = 1.0` is read as the string it was
+    /// written as.
+    pub fn new_lenient(content: &str) -> Result<(Self, Vec<String>), toml::de::Error> {
+        #[derive(Deserialize)]
+        struct Raw {
+            project: Option<RawProject>,
+        }
+        #[derive(Deserialize)]
+        struct RawProject {
+            version: Option<toml::Spanned<toml::Value>>,
+        }
+
+        let mut warnings = Vec::new();
+        let mut table: toml::Table = toml::de::from_str(content)?;
+        let raw: Raw = toml::de::from_str(content)?;
+        if let Some(version) = raw.project.and_then(|project| project.version) {
+            if version.get_ref().is_integer() || version.get_ref().is_float() {
+                let written = content[version.span()].to_string();
+                warnings.push(format!(
+                    "`project.version` must be a string, reading `{written}` as \"{written}\""
+                ));
+                if let Some(project) = table.get_mut("project").and_then(|p| p.as_table_mut()) {
+                    project.insert("version".to_string(), toml::Value::String(written));
+                }
+            }
+        }
+        Ok((toml::Value::Table(table).try_into()?, warnings))
+    }
+
+    /// Parse `pyproject.toml` content like [`PyProjectToml::new`], but reject unknown keys at the
+    /// top level and in the `[project]` and `[build-system]` tables, e.g. `[porject]`.
+    ///
+    /// The keys in `[tool]` are not checked. The first unknown key is reported, with its span.
+    pub fn new_strict(content: &str) -> Result<Self, StrictError> {
+        type Keys = IndexMap<toml::Spanned<String>, serde::de::IgnoredAny>;
+        #[derive(Deserialize)]
+        struct Tables {
+            #[serde(rename = "build-system")]
+            build_system: Option<Keys>,
+            project: Option<Keys>,
+        }
+        const TOP_LEVEL: [&str; 4] = ["build-system", "project", "dependency-groups", "tool"];
+        const BUILD_SYSTEM: [&str; 3] = ["requires", "build-backend", "backend-path"];
+        const PROJECT: [&str; 18] = [
+            "name",
+            "version",
+            "description",
+            "readme",
+            "requires-python",
+            "license",
+            "license-files",
+            "authors",
+            "maintainers",
+            "keywords",
+            "classifiers",
+            "urls",
+            "entry-points",
+            "scripts",
+            "gui-scripts",
+            "dependencies",
+            "optional-dependencies",
+            "dynamic",
+        ];
+
+        let top_level: Keys = toml::de::from_str(content)?;
+        let tables: Tables = toml::de::from_str(content)?;
+        let keys = [
+            ("", Some(top_level), &TOP_LEVEL[..]),
+            ("build-system.", tables.build_system, &BUILD_SYSTEM[..]),
+            ("project.", tables.project, &PROJECT[..]),
+        ];
+        for (prefix, table, known) in keys {
+            for key in table.iter().flat_map(|table| table.keys()) {
+                if !known.contains(&key.get_ref().as_str()) {
+                    return Err(StrictError::UnknownKey {
+                        key: format!("{prefix}{}", key.get_ref()),
+                        span: key.span(),
+                    });
+                }
+            }
+        }
+        Ok(Self::new(content)?)
+    }
+
+    /// Serialize to `pyproject.toml` content.
+    ///
+    /// The keys of `[project]` follow the field order of PEP 621, starting with `name`, `version`
+    /// and `description`, while maps such as `urls` keep their order. Unset fields are omitted,
+    /// and nested tables such as `[project.optional-dependencies]` are
+    /// emitted after the plain keys of their parent table.
+    pub fn to_toml_string(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{DependencyGroupSpecifier, License, PyProjectToml, ReadMe};
+    use super::{DependencyGroupSpecifier, License, PyProjectToml, ReadMe, RequirementSource};
     use pep440_rs::{Version, VersionSpecifiers};
-    use pep508_rs::Requirement;
+    use pep508_rs::{ExtraName, PackageName, Requirement, VerbatimUrl};
     use std::path::PathBuf;
     use std::str::FromStr;
 
@@ -466,6 +1552,83 @@ readme = {text = "ReadMe!", content-type = "text/plain"}
         );
     }
 
+    #[test]
+    fn test_readme_content_type_charset() {
+        let source = r#"[project]
+name = "spam"
+readme = {file = "README.md", content-type = "text/markdown; charset=UTF-8"}
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let readme = project_toml.project.unwrap().readme.unwrap();
+        assert_eq!(readme.content_type_base(), Some("text/markdown"));
+        assert_eq!(readme.content_type_charset(), Some("UTF-8"));
+
+        let readme = ReadMe::RelativePath("README.md".to_string());
+        assert_eq!(readme.content_type_base(), Some("text/markdown"));
+        assert_eq!(readme.content_type_charset(), None);
+    }
+
+    #[test]
+    fn test_readme_validate() {
+        let table = |file: Option<&str>, text: Option<&str>| ReadMe::Table {
+            file: file.map(ToString::to_string),
+            text: text.map(ToString::to_string),
+            content_type: Some("text/markdown".to_string()),
+        };
+        assert_eq!(
+            table(Some("README.md"), Some("Spam"))
+                .validate()
+                .unwrap_err()
+                .to_string(),
+            "`project.readme` must set only one of `file` and `text`, not both"
+        );
+        assert_eq!(
+            table(None, None).validate().unwrap_err().to_string(),
+            "`project.readme` must set one of `file` and `text`"
+        );
+        table(Some("README.md"), None).validate().unwrap();
+        table(None, Some("Spam")).validate().unwrap();
+
+        let text_only = ReadMe::Table {
+            file: None,
+            text: Some("Spam".to_string()),
+            content_type: None,
+        };
+        assert_eq!(
+            text_only.validate().unwrap_err().to_string(),
+            "`project.readme` sets `text`, so it must also set `content-type`"
+        );
+    }
+
+    #[test]
+    fn test_readme_content_type() {
+        for (file, content_type) in [
+            ("README.md", Some("text/markdown")),
+            ("docs/README.rst", Some("text/x-rst")),
+            ("README.TXT", Some("text/plain")),
+            ("README.adoc", None),
+            ("README", None),
+        ] {
+            assert_eq!(
+                ReadMe::RelativePath(file.to_string()).content_type(),
+                content_type
+            );
+            let table = ReadMe::Table {
+                file: Some(file.to_string()),
+                text: None,
+                content_type: None,
+            };
+            assert_eq!(table.content_type(), content_type);
+        }
+
+        let explicit = ReadMe::Table {
+            file: Some("README.md".to_string()),
+            text: None,
+            content_type: Some("text/plain".to_string()),
+        };
+        assert_eq!(explicit.content_type(), Some("text/plain"));
+    }
+
     #[test]
     fn test_parse_pyproject_toml_dependency_groups() {
         let source = r#"[dependency-groups]
@@ -502,20 +1665,111 @@ iota = [{include-group = "alpha"}]
     }
 
     #[test]
-    fn invalid_email() {
-        let source = r#"
+    fn test_from_path() {
+        let source = r#"[build-system]
+requires = ["maturin"]
+build-backend = "maturin"
+
 [project]
-name = "hello-world"
-version = "0.1.0"
-# Ensure that the spans from toml handle utf-8 correctly
-authors = [
-    { name = "Z͑ͫ̓ͪ̂ͫ̽͏̴̙̤̞͉͚̯̞̠͍A̴̵̜̰͔ͫ͗͢L̠ͨͧͩ͘G̴̻͈͍͔̹̑͗̎̅͛́Ǫ̵̹̻̝̳͂̌̌͘", email = 1 }
-]
+name = "spam"
+version = "2020.0.0"
+dependencies = ["httpx"]
 "#;
-        let err = PyProjectToml::new(source).unwrap_err();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pyproject.toml");
+        std::fs::write(&path, source).unwrap();
         assert_eq!(
-            err.to_string(),
-            "TOML parse error at line 6, column 11
+            PyProjectToml::from_path(&path).unwrap(),
+            PyProjectToml::new(source).unwrap()
+        );
+
+        std::fs::write(&path, "[project]\nname = 1\n").unwrap();
+        let err = PyProjectToml::from_path(&path).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Failed to parse `{}`", path.display())
+        );
+        assert!(matches!(err, super::PyProjectTomlError::Toml { .. }));
+    }
+
+    #[test]
+    fn test_from_path_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pyproject.toml");
+        let err = PyProjectToml::from_path(&path).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("Failed to read `{}`", path.display())
+        );
+        let super::PyProjectTomlError::Io { err, .. } = err else {
+            panic!("expected an IO error, got {err}");
+        };
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_new_strict() {
+        let source = "[porject]\nname = \"spam\"\n";
+        let err = PyProjectToml::new_strict(source).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown key `porject`");
+        let super::StrictError::UnknownKey { span, .. } = err else {
+            panic!("expected an unknown key, got {err}");
+        };
+        assert_eq!(&source[span], "porject");
+
+        let source = "[project]\nname = \"spam\"\nversoin = \"1.0\"\n";
+        let err = PyProjectToml::new_strict(source).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown key `project.versoin`");
+        let super::StrictError::UnknownKey { span, .. } = err else {
+            panic!("expected an unknown key, got {err}");
+        };
+        assert_eq!(span, 24..31);
+
+        let source = r#"[build-system]
+requires = ["maturin"]
+build-backend = "maturin"
+
+[project]
+name = "spam"
+version = "2020.0.0"
+readme = "README.rst"
+license = {file = "LICENSE.txt"}
+authors = [{name = "Tzu-Ping Chung"}]
+dependencies = ["httpx"]
+
+[project.optional-dependencies]
+test = ["pytest < 5.0.0"]
+
+[project.urls]
+homepage = "example.com"
+
+[project.scripts]
+spam-cli = "spam:main_cli"
+
+[project.gui-scripts]
+spam-gui = "spam:main_gui"
+
+[project.entry-points."spam.magical"]
+tomatoes = "spam:main_tomatoes"
+"#;
+        PyProjectToml::new_strict(source).unwrap();
+    }
+
+    #[test]
+    fn invalid_email() {
+        let source = r#"
+[project]
+name = "hello-world"
+version = "0.1.0"
+# Ensure that the spans from toml handle utf-8 correctly
+authors = [
+    { name = "Z͑ͫ̓ͪ̂ͫ̽͏̴̙̤̞͉͚̯̞̠͍A̴̵̜̰͔ͫ͗͢L̠ͨͧͩ͘G̴̻͈͍͔̹̑͗̎̅͛́Ǫ̵̹̻̝̳͂̌̌͘", email = 1 }
+]
+"#;
+        let err = PyProjectToml::new(source).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TOML parse error at line 6, column 11
   |
 6 | authors = [
   |           ^
@@ -548,4 +1802,965 @@ a table with 'name' and/or 'email' keys
         assert_eq!(contact.name(), None);
         assert_eq!(contact.email(), Some("john@example.com"));
     }
+
+    #[test]
+    fn test_duplicate_entry_points() {
+        let source = r#"[project]
+name = "spam"
+
+[project.scripts]
+spam-cli = "spam:main_cli"
+
+[project.entry-points.console_scripts]
+Spam-CLI = "spam:other_cli"
+
+[project.entry-points."spam.magical"]
+tomatoes = "spam:main_tomatoes"
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let duplicates = project_toml.project.unwrap().duplicate_entry_points();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(
+            duplicates["console_scripts"],
+            vec!["spam-cli".to_string(), "Spam-CLI".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_license_deprecation() {
+        let spdx = License::Spdx("MIT OR Apache-2.0".to_string());
+        let text = License::Text {
+            text: "MIT License".to_string(),
+        };
+        let file = License::File {
+            file: PathBuf::from("LICENSE"),
+        };
+        assert!(!spdx.is_deprecated());
+        assert!(text.is_deprecated());
+        assert!(file.is_deprecated());
+        assert_eq!(spdx.to_spdx_hint().as_deref(), Some("MIT OR Apache-2.0"));
+        assert_eq!(text.to_spdx_hint(), None);
+        assert_eq!(file.to_spdx_hint(), None);
+
+        let mut project = super::Project::new("spam".to_string());
+        assert!(!project.uses_legacy_license());
+        project.license = Some(spdx);
+        assert!(!project.uses_legacy_license());
+        project.license = Some(file);
+        assert!(project.uses_legacy_license());
+    }
+
+    #[test]
+    fn test_license_file_patterns() {
+        let mut project = super::Project::new("spam".to_string());
+        assert!(project.license_file_patterns().is_empty());
+
+        project.license = Some(License::File {
+            file: PathBuf::from("LICENSE.txt"),
+        });
+        assert_eq!(project.license_file_patterns(), ["LICENSE.txt"]);
+
+        project.license_files = Some(vec!["LICENSES/*".to_string(), "NOTICE".to_string()]);
+        assert_eq!(
+            project.license_file_patterns(),
+            ["LICENSE.txt", "LICENSES/*", "NOTICE"]
+        );
+
+        project.license = Some(License::Spdx("MIT".to_string()));
+        assert_eq!(project.license_file_patterns(), ["LICENSES/*", "NOTICE"]);
+    }
+
+    #[test]
+    fn test_validate_license_list() {
+        let source = r#"[project]
+name = "spam"
+license = ["MIT", "Apache-2.0"]
+"#;
+        assert!(PyProjectToml::new(source).is_err());
+        assert_eq!(
+            PyProjectToml::validate_license(source)
+                .unwrap_err()
+                .to_string(),
+            "`project.license` must be a single SPDX expression, not a list, try `MIT OR Apache-2.0`"
+        );
+
+        let source = r#"[project]
+name = "spam"
+license = "MIT OR Apache-2.0"
+"#;
+        PyProjectToml::validate_license(source).unwrap();
+    }
+
+    #[test]
+    fn test_into_parts() {
+        let source = r#"[build-system]
+requires = ["maturin"]
+
+[project]
+name = "spam"
+"#;
+        let (build_system, project, dependency_groups) =
+            PyProjectToml::new(source).unwrap().into_parts();
+        assert_eq!(
+            build_system.unwrap().requires,
+            vec![Requirement::from_str("maturin").unwrap()]
+        );
+        assert_eq!(project.unwrap().name, "spam");
+        assert_eq!(dependency_groups, None);
+    }
+
+    #[test]
+    fn test_compatible_release_dependencies() {
+        let source = r#"[project]
+name = "spam"
+dependencies = ["httpx~=0.27", "django>=4,~=4.2.1", "attrs>=23"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let project = project_toml.project.as_ref().unwrap();
+        let names: Vec<String> = project
+            .compatible_release_dependencies()
+            .into_iter()
+            .map(|requirement| requirement.name.to_string())
+            .collect();
+        assert_eq!(names, vec!["httpx", "django"]);
+
+        let source = r#"[project]
+name = "spam"
+dependencies = ["httpx~=2"]
+"#;
+        assert!(PyProjectToml::new(source).is_err());
+    }
+
+    #[test]
+    fn test_referenced_packages() {
+        let source = r#"[build-system]
+requires = ["maturin>=1,<2"]
+
+[project]
+name = "spam"
+dependencies = ["httpx", "Django>2.1"]
+
+[project.optional-dependencies]
+test = ["pytest"]
+
+[dependency-groups]
+lint = ["Ruff", "django"]
+dev = [{include-group = "lint"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let packages: Vec<String> = project_toml
+            .referenced_packages()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(packages, ["django", "httpx", "maturin", "pytest", "ruff"]);
+    }
+
+    #[test]
+    fn test_license_classifiers() {
+        let source = r#"[project]
+name = "spam"
+classifiers = [
+  "Development Status :: 4 - Beta",
+  "License :: OSI Approved :: MIT License",
+  "Programming Language :: Python"
+]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let project = project_toml.project.as_ref().unwrap();
+        assert!(project.has_license_classifiers());
+        assert_eq!(
+            project.license_classifiers(),
+            vec!["License :: OSI Approved :: MIT License"]
+        );
+
+        assert!(!super::Project::new("spam".to_string()).has_license_classifiers());
+    }
+
+    #[test]
+    fn test_likely_missing_backend() {
+        let source = r#"[build-system]
+requires = ["hatchling"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let build_system = project_toml.build_system.unwrap();
+        assert_eq!(
+            build_system.likely_missing_backend().as_deref(),
+            Some("hatchling.build")
+        );
+
+        let source = r#"[build-system]
+requires = ["hatchling"]
+build-backend = "hatchling.build"
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        assert_eq!(
+            project_toml.build_system.unwrap().likely_missing_backend(),
+            None
+        );
+
+        let source = r#"[build-system]
+requires = ["setuptools", "wheel"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        assert_eq!(
+            project_toml.build_system.unwrap().likely_missing_backend(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_locations_of() {
+        let source = r#"[project]
+name = "spam"
+dependencies = ["httpx", "django>2.1; os_name != 'nt'", "django>2.0; os_name == 'nt'"]
+
+[project.optional-dependencies]
+test = ["pytest", "Django[bcrypt]"]
+docs = ["sphinx"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let django = PackageName::from_str("django").unwrap();
+        assert_eq!(
+            project_toml.locations_of(&django),
+            vec![
+                RequirementSource::Dependencies,
+                RequirementSource::OptionalDependencies("test".to_string())
+            ]
+        );
+        assert_eq!(
+            RequirementSource::OptionalDependencies("test".to_string()).to_string(),
+            "project.optional-dependencies.test"
+        );
+    }
+
+    #[test]
+    fn test_requirement_specifiers() {
+        let source = r#"[build-system]
+requires = ["maturin"]
+
+[project]
+name = "spam"
+dependencies = [
+  "httpx",
+  "gidgethub[httpx]>4.0.0",
+  "django>2.1; os_name != 'nt'",
+  "django>2.0; os_name == 'nt'"
+]
+
+[project.optional-dependencies]
+test = ["pytest < 5.0.0", "pytest-cov[all]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let specifiers = project_toml.requirement_specifiers();
+        // `maturin`, the four base dependencies and the two in `test`
+        assert_eq!(specifiers.len(), 7);
+        assert_eq!(specifiers[0].0, RequirementSource::BuildSystem);
+        assert!(specifiers[0].2.is_empty());
+        let (source, name, gidgethub) = &specifiers[2];
+        assert_eq!(*source, RequirementSource::Dependencies);
+        assert_eq!(*name, PackageName::from_str("gidgethub").unwrap());
+        assert_eq!(gidgethub.to_string(), ">4.0.0");
+    }
+
+    #[test]
+    fn test_extras_requested_on() {
+        let source = r#"[project]
+name = "spam"
+dependencies = ["pytest"]
+
+[project.optional-dependencies]
+test = ["pytest[A]", "httpx[http2]"]
+
+[dependency-groups]
+dev = ["PyTest[b,a]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let extras = project_toml.extras_requested_on(&PackageName::from_str("pytest").unwrap());
+        assert_eq!(
+            extras,
+            ["a", "b"]
+                .iter()
+                .map(|extra| ExtraName::from_str(extra).unwrap())
+                .collect()
+        );
+    }
+
+    #[test]
+    fn test_self_references() {
+        let source = r#"[project]
+name = "Spam"
+dependencies = ["httpx"]
+
+[project.optional-dependencies]
+test = ["pytest"]
+all = ["spam[test]"]
+
+[dependency-groups]
+dev = ["SPAM[test]", "ruff"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        assert_eq!(
+            project_toml.self_references(),
+            vec![
+                (
+                    RequirementSource::OptionalDependencies("all".to_string()),
+                    "spam[test]".to_string()
+                ),
+                (
+                    RequirementSource::DependencyGroup("dev".to_string()),
+                    Requirement::<VerbatimUrl>::from_str("SPAM[test]")
+                        .unwrap()
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_value() {
+        let value = toml::Value::Table(toml::toml! {
+            [project]
+            name = "spam"
+            version = "1.0"
+            dependencies = ["httpx"]
+
+            [dependency-groups]
+            dev = ["ruff", { include-group = "test" }]
+            test = ["pytest"]
+        });
+        let expected = PyProjectToml::new(
+            r#"[project]
+name = "spam"
+version = "1.0"
+dependencies = ["httpx"]
+
+[dependency-groups]
+dev = ["ruff", {include-group = "test"}]
+test = ["pytest"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(PyProjectToml::from_value(value).unwrap(), expected);
+
+        let value = toml::Value::Table(toml::toml! {
+            [project]
+            version = "1.0"
+        });
+        assert!(PyProjectToml::from_value(value)
+            .unwrap_err()
+            .to_string()
+            .contains("missing field `name`"));
+    }
+
+    #[test]
+    fn test_dependency_group_specifier_accessors() {
+        let source = r#"[dependency-groups]
+test = ["pytest"]
+dev = [{include-group = "test"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let groups = project_toml.dependency_groups.as_ref().unwrap();
+        let requirement = &groups["test"][0];
+        assert_eq!(
+            requirement.as_requirement(),
+            Some(&Requirement::from_str("pytest").unwrap())
+        );
+        assert_eq!(requirement.as_include(), None);
+        let include = &groups["dev"][0];
+        assert_eq!(include.as_requirement(), None);
+        assert_eq!(include.as_include(), Some("test"));
+    }
+
+    #[test]
+    fn test_name_collisions() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+Dev = ["pytest"]
+docs = ["sphinx"]
+
+[dependency-groups]
+dev = ["ruff"]
+lint = ["mypy"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        assert_eq!(project_toml.name_collisions(), vec!["dev".to_string()]);
+    }
+
+    #[test]
+    fn test_normalized_optional_dependencies() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+My_Extra = ["pytest"]
+other = ["sphinx"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let project = project_toml.project.as_ref().unwrap();
+        let extras = project.normalized_optional_dependencies().unwrap();
+        let names: Vec<String> = extras.keys().map(ToString::to_string).collect();
+        assert_eq!(names, ["my-extra", "other"]);
+
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+group_one = ["pytest"]
+group-one = ["mypy"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let project = project_toml.project.as_ref().unwrap();
+        assert_eq!(
+            project
+                .normalized_optional_dependencies()
+                .unwrap_err()
+                .to_string(),
+            "The extras `group_one` and `group-one` are the same extra after PEP 685 normalization"
+        );
+    }
+
+    #[test]
+    fn test_normalization_collisions() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+my-extra = ["pytest"]
+other = ["sphinx"]
+my_extra = ["mypy"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let project = project_toml.project.as_ref().unwrap();
+        assert_eq!(
+            project.normalization_collisions(),
+            vec![(
+                "my-extra".to_string(),
+                vec!["my-extra".to_string(), "my_extra".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_new_lenient_numeric_version() {
+        let source = r#"[project]
+name = "spam"
+version = 1.10
+"#;
+        assert!(PyProjectToml::new(source).is_err());
+        let (project_toml, warnings) = PyProjectToml::new_lenient(source).unwrap();
+        assert_eq!(
+            project_toml.project.unwrap().version,
+            Some(Version::from_str("1.10").unwrap())
+        );
+        assert_eq!(
+            warnings,
+            vec!["`project.version` must be a string, reading `1.10` as \"1.10\""]
+        );
+    }
+
+    #[test]
+    fn test_to_toml_string_minimal_project() {
+        let project_toml = PyProjectToml {
+            build_system: None,
+            project: Some(super::Project::new("spam".to_string())),
+            dependency_groups: None,
+            tool: None,
+        };
+        assert_eq!(
+            project_toml.to_toml_string().unwrap(),
+            "[project]\nname = \"spam\"\n"
+        );
+    }
+
+    #[test]
+    fn test_to_toml_string_nested_tables() {
+        let source = r#"[project]
+name = "spam"
+dependencies = ["httpx"]
+
+[project.optional-dependencies]
+test = ["pytest"]
+
+[project.urls]
+homepage = "example.com"
+"#;
+        let mut project_toml = PyProjectToml::new(source).unwrap();
+        // Set a plain key after the tables were parsed
+        project_toml.project.as_mut().unwrap().description = Some("Spam".to_string());
+
+        let serialized = project_toml.to_toml_string().unwrap();
+        assert_eq!(PyProjectToml::new(&serialized).unwrap(), project_toml);
+        let description = serialized.find("description = ").unwrap();
+        let optional_dependencies = serialized.find("[project.optional-dependencies]").unwrap();
+        assert!(description < optional_dependencies);
+    }
+
+    #[test]
+    #[cfg(not(feature = "requirements-txt"))]
+    fn test_path_specifier_requires_feature() {
+        let source = r#"[dependency-groups]
+dev = [{path = "requirements.txt"}]
+"#;
+        assert!(PyProjectToml::new(source).is_err());
+    }
+
+    #[test]
+    fn test_dependency_group_specifier_serialization() {
+        let include = DependencyGroupSpecifier::Table {
+            include_group: "test".to_string(),
+        };
+        let mut table = toml::Table::new();
+        table.insert("include-group".to_string(), "test".into());
+        assert_eq!(
+            toml::Value::try_from(&include).unwrap(),
+            toml::Value::Table(table)
+        );
+        let requirement =
+            DependencyGroupSpecifier::String(Requirement::from_str("pytest>=8").unwrap());
+        assert_eq!(
+            toml::Value::try_from(&requirement).unwrap(),
+            toml::Value::String(
+                Requirement::<VerbatimUrl>::from_str("pytest>=8")
+                    .unwrap()
+                    .to_string()
+            )
+        );
+
+        let source = r#"[dependency-groups]
+test = ["pytest>=8"]
+dev = [{include-group = "test"}, "ruff"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let serialized = project_toml.to_toml_string().unwrap();
+        assert_eq!(PyProjectToml::new(&serialized).unwrap(), project_toml);
+    }
+
+    #[test]
+    fn test_builder() {
+        assert_eq!(
+            PyProjectToml::builder().build(),
+            PyProjectToml {
+                build_system: None,
+                project: None,
+                dependency_groups: None,
+                tool: None,
+            }
+        );
+
+        let full = PyProjectToml::new(
+            r#"[build-system]
+requires = ["maturin>=1,<2"]
+build-backend = "maturin"
+
+[project]
+name = "spam"
+
+[dependency-groups]
+dev = ["pytest"]
+
+[tool.maturin]
+bindings = "pyo3"
+"#,
+        )
+        .unwrap();
+        let built = PyProjectToml::builder()
+            .build_system(full.build_system.clone().unwrap())
+            .project(super::Project::new("spam".to_string()))
+            .dependency_groups(full.dependency_groups.clone().unwrap())
+            .tool(full.tool.clone().unwrap())
+            .build();
+        assert_eq!(built, full);
+    }
+
+    #[test]
+    fn test_to_toml_string_round_trip() {
+        let source = r#"[build-system]
+requires = ["maturin"]
+build-backend = "maturin"
+
+[project]
+name = "spam"
+version = "2020.0.0"
+description = "Lovely Spam! Wonderful Spam!"
+readme = "README.rst"
+requires-python = ">=3.8"
+license = {file = "LICENSE.txt"}
+keywords = ["egg", "bacon", "sausage"]
+authors = [{email = "hi@pradyunsg.me"}, {name = "Tzu-Ping Chung"}]
+dependencies = ["httpx", "django>2.1; os_name != 'nt'"]
+
+[project.optional-dependencies]
+test = ["pytest < 5.0.0", "pytest-cov[all]"]
+
+[project.urls]
+homepage = "example.com"
+
+[project.scripts]
+spam-cli = "spam:main_cli"
+
+[project.entry-points."spam.magical"]
+tomatoes = "spam:main_tomatoes"
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let serialized = project_toml.to_toml_string().unwrap();
+        assert_eq!(PyProjectToml::new(&serialized).unwrap(), project_toml);
+
+        let name = serialized.find("name = ").unwrap();
+        let version = serialized.find("version = ").unwrap();
+        let description = serialized.find("description = ").unwrap();
+        assert!(name < version && version < description);
+        assert!(!serialized.contains("license-files"));
+    }
+
+    #[test]
+    fn test_build_system_effective() {
+        let source = r#"[build-system]
+requires = ["maturin"]
+build-backend = "maturin"
+"#;
+        let configured = PyProjectToml::new(source).unwrap().build_system.unwrap();
+        assert_eq!(configured.effective_backend(), "maturin");
+        assert_eq!(
+            configured.effective_requires(),
+            vec![Requirement::from_str("maturin").unwrap()]
+        );
+
+        let fallback = super::BuildSystem {
+            requires: Vec::new(),
+            build_backend: None,
+            backend_path: None,
+        };
+        assert_eq!(
+            fallback.effective_backend(),
+            "setuptools.build_meta:__legacy__"
+        );
+        assert_eq!(
+            fallback.effective_requires(),
+            vec![
+                Requirement::from_str("setuptools").unwrap(),
+                Requirement::from_str("wheel").unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_backend_path() {
+        let build_system = |backend_path: &str| {
+            super::BuildSystem {
+                requires: Vec::new(),
+                build_backend: Some("backend".to_string()),
+                backend_path: Some(vec![backend_path.to_string()]),
+            }
+            .validate_backend_path()
+        };
+        build_system(".").unwrap();
+        build_system("backend").unwrap();
+        build_system("backend/../src").unwrap();
+        assert_eq!(
+            build_system("/abs").unwrap_err().to_string(),
+            "The `build-system.backend-path` entry `/abs` must be a relative path"
+        );
+        assert_eq!(
+            build_system("../escape").unwrap_err().to_string(),
+            "The `build-system.backend-path` entry `../escape` points outside the project root"
+        );
+    }
+
+    #[test]
+    fn test_apply_defaults() {
+        let mut project_toml = PyProjectToml::new("[project]\nname = \"spam\"\n").unwrap();
+        project_toml.apply_defaults();
+        let build_system = project_toml.build_system.as_ref().unwrap();
+        assert_eq!(
+            build_system.requires,
+            vec![
+                Requirement::from_str("setuptools").unwrap(),
+                Requirement::from_str("wheel").unwrap()
+            ]
+        );
+        assert_eq!(
+            build_system.build_backend.as_deref(),
+            Some("setuptools.build_meta:__legacy__")
+        );
+        assert_eq!(
+            project_toml.project.as_ref().unwrap().license_files,
+            Some(vec![
+                "LICEN[CS]E*".to_string(),
+                "COPYING*".to_string(),
+                "NOTICE*".to_string(),
+                "AUTHORS*".to_string()
+            ])
+        );
+
+        // Explicit values are kept
+        let source = r#"[build-system]
+requires = ["maturin"]
+build-backend = "maturin"
+
+[project]
+name = "spam"
+license = {file = "LICENSE.txt"}
+"#;
+        let mut explicit = PyProjectToml::new(source).unwrap();
+        explicit.apply_defaults();
+        assert_eq!(
+            explicit.build_system.unwrap().build_backend.as_deref(),
+            Some("maturin")
+        );
+        // A legacy license table must not be combined with `license-files`
+        assert_eq!(explicit.project.unwrap().license_files, None);
+
+        let source = r#"[project]
+name = "spam"
+dynamic = ["license-files"]
+"#;
+        let mut dynamic = PyProjectToml::new(source).unwrap();
+        dynamic.apply_defaults();
+        assert_eq!(dynamic.project.unwrap().license_files, None);
+
+        let source = r#"[project]
+name = "spam"
+license = "MIT"
+"#;
+        let mut spdx = PyProjectToml::new(source).unwrap();
+        spdx.apply_defaults();
+        assert_eq!(
+            spdx.project.unwrap().license_files.map(|files| files.len()),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn test_tool_table() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        #[serde(rename_all = "kebab-case")]
+        struct MyTool {
+            line_length: u32,
+        }
+
+        let source = r#"[project]
+name = "spam"
+
+[tool.mytool]
+line-length = 100
+nested = { enabled = true, paths = ["src", "tests"] }
+
+[tool.other]
+ratio = 0.5
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let serialized = project_toml.to_toml_string().unwrap();
+        assert_eq!(PyProjectToml::new(&serialized).unwrap(), project_toml);
+        assert_eq!(
+            project_toml.tool.as_ref().unwrap()["mytool"]["nested"]["paths"],
+            toml::Value::Array(vec!["src".into(), "tests".into()])
+        );
+        assert_eq!(
+            project_toml
+                .tool_table::<MyTool>("mytool")
+                .unwrap()
+                .unwrap(),
+            MyTool { line_length: 100 }
+        );
+        assert!(project_toml.tool_table::<MyTool>("other").unwrap().is_err());
+        assert!(project_toml.tool_table::<MyTool>("missing").is_none());
+    }
+
+    #[test]
+    fn test_compatible_interpreters() {
+        let interpreters: Vec<(String, Version)> = [
+            ("CPython", "3.8.10"),
+            ("PyPy", "3.9.18"),
+            ("CPython", "3.12.1"),
+            ("PyPy", "3.7.13"),
+        ]
+        .into_iter()
+        .map(|(name, version)| (name.to_string(), Version::from_str(version).unwrap()))
+        .collect();
+
+        let mut project = super::Project::new("spam".to_string());
+        assert_eq!(project.compatible_interpreters(&interpreters), interpreters);
+
+        project.requires_python = Some(VersionSpecifiers::from_str(">=3.9").unwrap());
+        assert_eq!(
+            project.compatible_interpreters(&interpreters),
+            vec![interpreters[1].clone(), interpreters[2].clone()]
+        );
+    }
+
+    #[test]
+    fn test_supports_python() {
+        let version = |version: &str| Version::from_str(version).unwrap();
+        let mut project = super::Project::new("spam".to_string());
+        assert_eq!(project.supports_python(&version("3.12")), None);
+
+        project.requires_python = Some(VersionSpecifiers::from_str(">=3.8").unwrap());
+        assert_eq!(project.supports_python(&version("3.7")), Some(false));
+        assert_eq!(project.supports_python(&version("3.8")), Some(true));
+        assert_eq!(project.supports_python(&version("3.12")), Some(true));
+    }
+
+    #[test]
+    fn test_classifier_python_conflicts() {
+        let source = r#"[project]
+name = "spam"
+requires-python = ">=3.9"
+classifiers = [
+  "Programming Language :: Python :: 3",
+  "Programming Language :: Python :: 3 :: Only",
+  "Programming Language :: Python :: 3.7",
+  "Programming Language :: Python :: 3.9",
+  "Programming Language :: Python :: 3.13",
+]
+"#;
+        let project = PyProjectToml::new(source).unwrap().project.unwrap();
+        assert_eq!(
+            project.classifier_python_conflicts(),
+            vec![Version::from_str("3.7").unwrap()]
+        );
+
+        // Later patch releases of the minor version are allowed
+        for (requires_python, conflicts) in [
+            (">=3.8.1", vec!["3.7"]),
+            (">3.9", vec!["3.7", "3.8"]),
+            (">=3.8.1,!=3.8.1,!=3.8.2", vec!["3.7"]),
+            ("<3.9.0", vec!["3.9", "3.10"]),
+            ("~=3.8.1", vec!["3.7", "3.9", "3.10"]),
+        ] {
+            let source = format!(
+                r#"[project]
+name = "spam"
+requires-python = "{requires_python}"
+classifiers = [
+  "Programming Language :: Python :: 3.7",
+  "Programming Language :: Python :: 3.8",
+  "Programming Language :: Python :: 3.9",
+  "Programming Language :: Python :: 3.10",
+]
+"#
+            );
+            let project = PyProjectToml::new(&source).unwrap().project.unwrap();
+            let expected: Vec<Version> = conflicts
+                .into_iter()
+                .map(|version| Version::from_str(version).unwrap())
+                .collect();
+            assert_eq!(
+                project.classifier_python_conflicts(),
+                expected,
+                "{requires_python}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_homepage() {
+        let source = r#"[project]
+name = "spam"
+
+[project.urls]
+documentation = "readthedocs.org"
+homepage = "example.com"
+repository = "github.com"
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        assert_eq!(
+            project_toml.project.unwrap().homepage(),
+            Some("example.com")
+        );
+
+        let mut project = super::Project::new("spam".to_string());
+        assert_eq!(project.homepage(), None);
+        project.urls = Some(
+            [
+                ("Documentation", "readthedocs.org"),
+                ("Repository", "github.com/me/spam"),
+            ]
+            .into_iter()
+            .map(|(label, url)| (label.to_string(), url.to_string()))
+            .collect(),
+        );
+        assert_eq!(project.homepage(), Some("github.com/me/spam"));
+    }
+
+    #[test]
+    fn test_validate_urls() {
+        let source = r#"[project]
+name = "spam"
+
+[project.urls]
+Home-page = "https://example.com"
+Documentation = "https://example.com/docs"
+"home page" = "https://example.org"
+"#;
+        let project = PyProjectToml::new(source).unwrap().project.unwrap();
+        assert_eq!(
+            project.validate_urls().unwrap_err().to_string(),
+            "The `project.urls` labels `Home-page` and `home page` are the same label for PyPI"
+        );
+
+        let source = r#"[project]
+name = "spam"
+
+[project.urls]
+homepage = "example.com"
+documentation = "readthedocs.org"
+repository = "github.com"
+changelog = "github.com/me/spam/blob/master/CHANGELOG.md"
+"#;
+        let project = PyProjectToml::new(source).unwrap().project.unwrap();
+        project.validate_urls().unwrap();
+    }
+
+    #[test]
+    fn test_iter_requirements() {
+        let source = r#"[project]
+name = "spam"
+dependencies = [
+  "httpx",
+  "gidgethub[httpx]>4.0.0",
+  "django>2.1; os_name != 'nt'",
+  "django>2.0; os_name == 'nt'"
+]
+
+[project.optional-dependencies]
+test = ["pytest < 5.0.0", "pytest-cov[all]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let project = project_toml.project.as_ref().unwrap();
+        let labels: Vec<String> = project
+            .iter_requirements()
+            .map(|(label, requirement)| format!("{label}: {}", requirement.name))
+            .collect();
+        assert_eq!(labels.len(), 6);
+        assert_eq!(
+            labels,
+            [
+                "dependencies: httpx",
+                "dependencies: gidgethub",
+                "dependencies: django",
+                "dependencies: django",
+                "optional:test: pytest",
+                "optional:test: pytest-cov",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_has_any_dependencies() {
+        let source = r#"[build-system]
+requires = ["maturin>=1.0,<2.0"]
+build-backend = "maturin"
+
+[project]
+name = "spam"
+"#;
+        let mut project = PyProjectToml::new(source).unwrap().project.unwrap();
+        assert!(!project.has_any_dependencies());
+
+        project.dynamic = Some(vec!["dependencies".to_string()]);
+        assert!(project.has_any_dependencies());
+    }
 }
@@ -0,0 +1,265 @@
+//! Validation and normalization of `project.license` SPDX expressions.
+
+use thiserror::Error;
+
+use crate::{License, Project};
+
+/// The `License ::` classifiers that correspond to exactly one SPDX license identifier.
+///
+/// Classifiers such as `License :: OSI Approved :: BSD License` that cover several licenses or
+/// versions are deliberately missing.
+const CLASSIFIER_TO_SPDX: &[(&str, &str)] = &[
+    (
+        "License :: CC0 1.0 Universal (CC0 1.0) Public Domain Dedication",
+        "CC0-1.0",
+    ),
+    (
+        "License :: OSI Approved :: Boost Software License 1.0 (BSL-1.0)",
+        "BSL-1.0",
+    ),
+    (
+        "License :: OSI Approved :: Eclipse Public License 1.0 (EPL-1.0)",
+        "EPL-1.0",
+    ),
+    (
+        "License :: OSI Approved :: Eclipse Public License 2.0 (EPL-2.0)",
+        "EPL-2.0",
+    ),
+    (
+        "License :: OSI Approved :: European Union Public Licence 1.2 (EUPL 1.2)",
+        "EUPL-1.2",
+    ),
+    (
+        "License :: OSI Approved :: GNU Affero General Public License v3",
+        "AGPL-3.0-only",
+    ),
+    (
+        "License :: OSI Approved :: GNU Affero General Public License v3 or later (AGPLv3+)",
+        "AGPL-3.0-or-later",
+    ),
+    (
+        "License :: OSI Approved :: GNU General Public License v2 (GPLv2)",
+        "GPL-2.0-only",
+    ),
+    (
+        "License :: OSI Approved :: GNU General Public License v2 or later (GPLv2+)",
+        "GPL-2.0-or-later",
+    ),
+    (
+        "License :: OSI Approved :: GNU General Public License v3 (GPLv3)",
+        "GPL-3.0-only",
+    ),
+    (
+        "License :: OSI Approved :: GNU General Public License v3 or later (GPLv3+)",
+        "GPL-3.0-or-later",
+    ),
+    (
+        "License :: OSI Approved :: GNU Lesser General Public License v2 (LGPLv2)",
+        "LGPL-2.0-only",
+    ),
+    (
+        "License :: OSI Approved :: GNU Lesser General Public License v2 or later (LGPLv2+)",
+        "LGPL-2.0-or-later",
+    ),
+    (
+        "License :: OSI Approved :: GNU Lesser General Public License v3 (LGPLv3)",
+        "LGPL-3.0-only",
+    ),
+    (
+        "License :: OSI Approved :: GNU Lesser General Public License v3 or later (LGPLv3+)",
+        "LGPL-3.0-or-later",
+    ),
+    ("License :: OSI Approved :: ISC License (ISCL)", "ISC"),
+    ("License :: OSI Approved :: MIT License", "MIT"),
+    (
+        "License :: OSI Approved :: MIT No Attribution License (MIT-0)",
+        "MIT-0",
+    ),
+    (
+        "License :: OSI Approved :: Mozilla Public License 1.1 (MPL 1.1)",
+        "MPL-1.1",
+    ),
+    (
+        "License :: OSI Approved :: Mozilla Public License 2.0 (MPL 2.0)",
+        "MPL-2.0",
+    ),
+    (
+        "License :: OSI Approved :: Python Software Foundation License",
+        "PSF-2.0",
+    ),
+    (
+        "License :: OSI Approved :: The Unlicense (Unlicense)",
+        "Unlicense",
+    ),
+    (
+        "License :: OSI Approved :: Universal Permissive License (UPL)",
+        "UPL-1.0",
+    ),
+    ("License :: OSI Approved :: zlib/libpng License", "Zlib"),
+];
+
+/// An invalid SPDX license expression in `project.license`
+#[derive(Debug, Error)]
+pub enum SpdxError {
+    #[error("Invalid SPDX expression `{expression}` at position {position}: {reason}")]
+    Invalid {
+        expression: String,
+        /// The byte offset of the first invalid token
+        position: usize,
+        reason: String,
+    },
+}
+
+impl License {
+    /// Check that a `license = "..."` string is a valid SPDX expression, with known license IDs
+    /// combined by `AND`, `OR` and `WITH` and balanced parentheses.
+    ///
+    /// The table variants are not SPDX expressions and are always valid.
+    pub fn validate_spdx(&self) -> Result<(), SpdxError> {
+        let expression = match self {
+            License::Spdx(expression) => expression,
+            License::File { .. } | License::Text { .. } => return Ok(()),
+        };
+        spdx::Expression::parse(expression)
+            .map(|_| ())
+            .map_err(|err| SpdxError::Invalid {
+                expression: expression.clone(),
+                position: err.span.start,
+                reason: err.reason.to_string(),
+            })
+    }
+
+    /// Render a `license = "..."` SPDX expression in canonical form.
+    ///
+    /// Operators are uppercased, whitespace is collapsed to single spaces and license and
+    /// exception identifiers get the casing from the SPDX license list, so `mit or apache-2.0`
+    /// becomes `MIT OR Apache-2.0`. Returns `None` for the table variants and for expressions
+    /// that are not valid SPDX after normalization.
+    pub fn normalized_spdx(&self) -> Option<String> {
+        let expression = match self {
+            License::Spdx(expression) => expression,
+            License::File { .. } | License::Text { .. } => return None,
+        };
+
+        let spaced = expression.replace('(', " ( ").replace(')', " ) ");
+        let mut rendered = String::with_capacity(expression.len());
+        for word in spaced.split_whitespace() {
+            if !rendered.is_empty() && !rendered.ends_with('(') && word != ")" {
+                rendered.push(' ');
+            }
+            if ["AND", "OR", "WITH"]
+                .iter()
+                .any(|operator| operator.eq_ignore_ascii_case(word))
+            {
+                rendered.push_str(&word.to_ascii_uppercase());
+            } else {
+                rendered.push_str(&canonical_identifier(word));
+            }
+        }
+
+        spdx::Expression::parse(&rendered).ok()?;
+        Some(rendered)
+    }
+}
+
+impl Project {
+    /// Map the deprecated `License ::` classifiers to SPDX license identifiers, e.g. to migrate to
+    /// a PEP 639 `license` expression.
+    ///
+    /// Classifiers without a single matching SPDX identifier, such as
+    /// `License :: OSI Approved :: BSD License`, are skipped. Each identifier is returned once, in
+    /// classifier order.
+    pub fn spdx_from_classifiers(&self) -> Vec<String> {
+        let mut identifiers = Vec::new();
+        for classifier in self.license_classifiers() {
+            let Some((_, identifier)) = CLASSIFIER_TO_SPDX
+                .iter()
+                .find(|(known, _)| *known == classifier)
+            else {
+                continue;
+            };
+            if !identifiers.iter().any(|known| known == identifier) {
+                identifiers.push(identifier.to_string());
+            }
+        }
+        identifiers
+    }
+}
+
+/// Look up the canonical casing of a license or exception identifier, keeping a trailing `+`.
+///
+/// Unknown identifiers and `LicenseRef-` references are returned unchanged.
+fn canonical_identifier(word: &str) -> String {
+    let (id, plus) = match word.strip_suffix('+') {
+        Some(id) => (id, "+"),
+        None => (word, ""),
+    };
+    let known = spdx::identifiers::LICENSES
+        .iter()
+        .map(|(name, _, _)| *name)
+        .chain(spdx::identifiers::EXCEPTIONS.iter().map(|(name, _)| *name))
+        .find(|name| name.eq_ignore_ascii_case(id));
+    match known {
+        Some(name) => format!("{name}{plus}"),
+        None => word.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CLASSIFIER_TO_SPDX;
+    use crate::{License, Project};
+
+    #[test]
+    fn test_spdx_from_classifiers() {
+        let mut project = Project::new("spam".to_string());
+        project.classifiers = Some(vec![
+            "Programming Language :: Python".to_string(),
+            "License :: OSI Approved :: MIT License".to_string(),
+            "License :: OSI Approved :: BSD License".to_string(),
+        ]);
+        assert_eq!(project.spdx_from_classifiers(), vec!["MIT"]);
+
+        for (_, identifier) in CLASSIFIER_TO_SPDX {
+            License::Spdx(identifier.to_string())
+                .validate_spdx()
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_validate_spdx() {
+        let spdx = |expression: &str| License::Spdx(expression.to_string()).validate_spdx();
+        spdx("MIT OR BSD-3-Clause").unwrap();
+        spdx("MIT AND (Apache-2.0 OR BSD-2-Clause)").unwrap();
+        assert_eq!(
+            spdx("MIT OR").unwrap_err().to_string(),
+            "Invalid SPDX expression `MIT OR` at position 6: expected one of `<license>`, `(` here"
+        );
+        License::File {
+            file: "LICENSE".into(),
+        }
+        .validate_spdx()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_normalized_spdx() {
+        let license = License::Spdx("mit   or (apache-2.0 with llvm-exception)".to_string());
+        assert_eq!(
+            license.normalized_spdx().as_deref(),
+            Some("MIT OR (Apache-2.0 WITH LLVM-exception)")
+        );
+        assert_eq!(
+            License::Spdx("mit or not-a-license".to_string()).normalized_spdx(),
+            None
+        );
+        assert_eq!(
+            License::Text {
+                text: "MIT".to_string()
+            }
+            .normalized_spdx(),
+            None
+        );
+    }
+}
@@ -0,0 +1,574 @@
+//! Analysis of the PEP 508 environment markers attached to requirements.
+
+use indexmap::IndexMap;
+use pep508_rs::{
+    ExtraName, MarkerEnvironment, MarkerExpression, MarkerTree, MarkerValueVersion, Requirement,
+};
+use std::str::FromStr;
+
+use crate::resolution::normalize_name;
+use crate::{
+    DependencyGroupSpecifier, InstallTarget, Project, PyProjectToml, ResolveError,
+    ResolveErrorKind, ResolvedDependencies,
+};
+
+impl Project {
+    /// Return the base dependencies whose markers apply in the given environment, i.e. what a
+    /// default install on that machine requires.
+    pub fn runtime_requirements_for(&self, env: &MarkerEnvironment) -> Vec<Requirement> {
+        self.dependencies
+            .iter()
+            .flatten()
+            .filter(|requirement| requirement.marker.evaluate(env, &[]))
+            .cloned()
+            .collect()
+    }
+
+    /// Return `requires-python` as a marker, e.g. `python_full_version >= '3.9'` for `>=3.9`.
+    fn requires_python_marker(&self) -> Option<MarkerTree> {
+        let requires_python = self.requires_python.as_ref()?;
+        let mut marker = MarkerTree::TRUE;
+        for specifier in requires_python.iter() {
+            marker.and(MarkerTree::expression(MarkerExpression::Version {
+                key: MarkerValueVersion::PythonFullVersion,
+                specifier: specifier.clone(),
+            }));
+        }
+        Some(marker)
+    }
+
+    /// Return the base and optional dependencies with a `python_version` or `python_full_version`
+    /// bound that is always true given `requires-python`, together with the requirement
+    /// rewritten without the redundant bound.
+    ///
+    /// For example with `requires-python = ">=3.9"`, `tomli; python_version >= "3.6"` can be
+    /// simplified to `tomli`. The marker is simplified in disjunctive normal form, so bounds
+    /// inside an `or` are removed too.
+    pub fn obsolete_python_markers(&self) -> Vec<(&Requirement, String)> {
+        let Some(requires_python) = self.requires_python_marker() else {
+            return Vec::new();
+        };
+
+        let requirements = self.dependencies.iter().flatten().chain(
+            self.optional_dependencies
+                .iter()
+                .flat_map(|extras| extras.values().flatten()),
+        );
+        let mut obsolete = Vec::new();
+        for requirement in requirements {
+            let mut marker = MarkerTree::FALSE;
+            for conjunction in requirement.marker.to_dnf() {
+                let mut clause = MarkerTree::TRUE;
+                for expression in conjunction {
+                    let expression = MarkerTree::expression(expression);
+                    // Drop the expressions that `requires-python` implies
+                    if !requires_python.is_disjoint(&expression.negate()) {
+                        clause.and(expression);
+                    }
+                }
+                marker.or(clause);
+            }
+            if marker == requirement.marker {
+                continue;
+            }
+            let mut simplified = requirement.clone();
+            simplified.marker = marker;
+            obsolete.push((requirement, simplified.to_string()));
+        }
+        obsolete
+    }
+
+    /// Whether the extras `a` and `b` can't both be active, e.g. for checking that a `cpu` and a
+    /// `gpu` extra are mutually exclusive by platform.
+    ///
+    /// An extra is active where it is empty or where the marker of at least one of its
+    /// requirements is true with the extra requested. The extras conflict if there is no
+    /// environment in which both are active, and at least one of them is active in `env`, since
+    /// extras that install nothing there can't get in each other's way. Unknown extras never
+    /// conflict.
+    pub fn extras_marker_conflict(&self, a: &str, b: &str, env: &MarkerEnvironment) -> bool {
+        let active_marker = |extra: &str| {
+            let optional_dependencies = self.optional_dependencies.as_ref()?;
+            let (name, requirements) = optional_dependencies
+                .iter()
+                .find(|(name, _)| normalize_name(name) == normalize_name(extra))?;
+            if requirements.is_empty() {
+                return Some(MarkerTree::TRUE);
+            }
+            let extra_names: Vec<ExtraName> = ExtraName::from_str(name).into_iter().collect();
+            let mut marker = MarkerTree::FALSE;
+            for requirement in requirements {
+                marker.or(requirement.marker.clone().simplify_extras(&extra_names));
+            }
+            Some(marker)
+        };
+        let (Some(a), Some(b)) = (active_marker(a), active_marker(b)) else {
+            return false;
+        };
+        a.is_disjoint(&b) && (a.evaluate(env, &[]) || b.evaluate(env, &[]))
+    }
+}
+
+impl ResolvedDependencies {
+    /// Keep only the requirements whose markers apply in the given environment, e.g. to show
+    /// what each extra and group installs on a specific machine.
+    ///
+    /// Requirements without a marker are always kept. The requirements of an extra are evaluated
+    /// with that extra requested, those of a dependency group without any extra.
+    pub fn filter_for_markers(&self, env: &MarkerEnvironment) -> ResolvedDependencies {
+        let filter = |requirements: &[Requirement], extras: &[ExtraName]| -> Vec<Requirement> {
+            requirements
+                .iter()
+                .filter(|requirement| requirement.marker.evaluate(env, extras))
+                .cloned()
+                .collect()
+        };
+        ResolvedDependencies {
+            optional_dependencies: self
+                .optional_dependencies
+                .iter()
+                .map(|(extra, requirements)| {
+                    let extras: Vec<ExtraName> =
+                        ExtraName::from_str(extra).ok().into_iter().collect();
+                    (extra.clone(), filter(requirements, &extras))
+                })
+                .collect(),
+            dependency_groups: self
+                .dependency_groups
+                .iter()
+                .map(|(group, requirements)| (group.clone(), filter(requirements, &[])))
+                .collect(),
+        }
+    }
+}
+
+impl PyProjectToml {
+    /// Return the distinct markers of the requirements of each extra and group, without resolving
+    /// self-references and includes, e.g. to see that a `gpu` extra only applies on some
+    /// platforms.
+    pub fn markers_per_target(&self) -> IndexMap<InstallTarget, Vec<String>> {
+        fn distinct_markers<'a>(
+            requirements: impl Iterator<Item = &'a Requirement>,
+        ) -> Vec<String> {
+            let mut markers = Vec::new();
+            for marker in requirements.filter_map(|requirement| requirement.marker.try_to_string())
+            {
+                if !markers.contains(&marker) {
+                    markers.push(marker);
+                }
+            }
+            markers
+        }
+
+        let mut markers = IndexMap::new();
+        let extras = self
+            .project
+            .iter()
+            .filter_map(|project| project.optional_dependencies.as_ref())
+            .flat_map(|extras| extras.iter());
+        for (extra, requirements) in extras {
+            markers.insert(
+                InstallTarget::Extra(extra.clone()),
+                distinct_markers(requirements.iter()),
+            );
+        }
+        for (group, specifiers) in self
+            .dependency_groups
+            .iter()
+            .flat_map(|groups| groups.iter())
+        {
+            let requirements = specifiers.iter().filter_map(|specifier| match specifier {
+                DependencyGroupSpecifier::String(requirement) => Some(requirement),
+                DependencyGroupSpecifier::Table { .. } => None,
+                #[cfg(feature = "requirements-txt")]
+                DependencyGroupSpecifier::Path { .. } => None,
+                #[cfg(feature = "include-extra")]
+                DependencyGroupSpecifier::IncludeExtra { .. } => None,
+            });
+            markers.insert(
+                InstallTarget::Group(group.clone()),
+                distinct_markers(requirements),
+            );
+        }
+        markers
+    }
+
+    /// Return the requirements to install for the given extras and dependency groups in the given
+    /// environment: the base dependencies followed by those of the resolved extras and groups,
+    /// keeping only those whose markers apply, without identical duplicates.
+    ///
+    /// Returns an error if a selected extra or group doesn't exist or resolution fails.
+    pub fn install_plan(
+        &self,
+        extras: &[&str],
+        groups: &[&str],
+        env: &MarkerEnvironment,
+    ) -> Result<Vec<Requirement>, ResolveError> {
+        // The resolved extras and groups are keyed by normalized name
+        fn select<'a>(
+            resolved: &'a IndexMap<String, Vec<Requirement>>,
+            name: &str,
+        ) -> Option<&'a Vec<Requirement>> {
+            resolved.get(&normalize_name(name))
+        }
+
+        let resolved = self.resolve()?;
+        let mut requirements: Vec<&Requirement> = self
+            .project
+            .iter()
+            .flat_map(|project| project.dependencies.iter().flatten())
+            .collect();
+        for extra in extras {
+            let selected = select(&resolved.optional_dependencies, extra)
+                .ok_or_else(|| ResolveErrorKind::UnknownExtra(extra.to_string()))?;
+            requirements.extend(selected);
+        }
+        for group in groups {
+            let selected = select(&resolved.dependency_groups, group)
+                .ok_or_else(|| ResolveErrorKind::UnknownGroup(group.to_string()))?;
+            requirements.extend(selected);
+        }
+
+        let extra_names: Vec<ExtraName> = extras
+            .iter()
+            .filter_map(|extra| ExtraName::from_str(extra).ok())
+            .collect();
+        let mut plan: Vec<Requirement> = Vec::new();
+        for requirement in requirements {
+            if requirement.marker.evaluate(env, &extra_names) && !plan.contains(requirement) {
+                plan.push(requirement.clone());
+            }
+        }
+        Ok(plan)
+    }
+
+    /// Return the distinct `python_version` and `python_full_version` markers of the build
+    /// requirements, e.g. to check that the build environment's Python satisfies them.
+    ///
+    /// The expressions of a marker that is a conjunction are reported individually, a marker
+    /// with an `or` mentioning the Python version is reported as a whole.
+    pub fn build_python_markers(&self) -> Vec<String> {
+        let is_python = |expression: &MarkerExpression| {
+            matches!(
+                expression,
+                MarkerExpression::Version {
+                    key: MarkerValueVersion::PythonVersion | MarkerValueVersion::PythonFullVersion,
+                    ..
+                }
+            )
+        };
+
+        let mut markers = Vec::new();
+        let requirements = self
+            .build_system
+            .iter()
+            .flat_map(|build_system| build_system.requires.iter());
+        for requirement in requirements {
+            let python_markers: Vec<String> = match requirement.marker.to_dnf().as_slice() {
+                [conjunction] => conjunction
+                    .iter()
+                    .filter(|expression| is_python(expression))
+                    .map(ToString::to_string)
+                    .collect(),
+                disjunction if disjunction.iter().flatten().any(is_python) => {
+                    requirement.marker.try_to_string().into_iter().collect()
+                }
+                _ => Vec::new(),
+            };
+            for python_marker in python_markers {
+                if !markers.contains(&python_marker) {
+                    markers.push(python_marker);
+                }
+            }
+        }
+        markers
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use pep508_rs::{MarkerEnvironment, MarkerEnvironmentBuilder, Requirement, VerbatimUrl};
+    use std::str::FromStr;
+
+    use crate::{InstallTarget, PyProjectToml};
+
+    /// A CPython 3.12 environment on Windows or Linux
+    pub(crate) fn environment(windows: bool) -> MarkerEnvironment {
+        let (os_name, platform_system, sys_platform) = if windows {
+            ("nt", "Windows", "win32")
+        } else {
+            ("posix", "Linux", "linux")
+        };
+        MarkerEnvironment::try_from(MarkerEnvironmentBuilder {
+            implementation_name: "cpython",
+            implementation_version: "3.12.1",
+            os_name,
+            platform_machine: "x86_64",
+            platform_python_implementation: "CPython",
+            platform_release: "",
+            platform_system,
+            platform_version: "",
+            python_full_version: "3.12.1",
+            python_version: "3.12",
+            sys_platform,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_runtime_requirements_for() {
+        let source = r#"[project]
+name = "spam"
+dependencies = [
+  "httpx",
+  "gidgethub[httpx]>4.0.0",
+  "django>2.1; os_name != 'nt'",
+  "django>2.0; os_name == 'nt'"
+]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let project = project_toml.project.as_ref().unwrap();
+        let to_strings = |requirements: Vec<Requirement>| {
+            requirements
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        let windows = to_strings(project.runtime_requirements_for(&environment(true)));
+        let linux = to_strings(project.runtime_requirements_for(&environment(false)));
+        assert_eq!(windows.len(), 3);
+        assert!(windows[2].starts_with("django>2.0"));
+        assert_eq!(linux.len(), 3);
+        assert!(linux[2].starts_with("django>2.1"));
+    }
+
+    #[test]
+    fn test_filter_for_markers() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+web = [
+  "httpx",
+  "django>2.1; os_name != 'nt'",
+  "django>2.0; os_name == 'nt'"
+]
+
+[dependency-groups]
+dev = ["pywin32; os_name == 'nt'", "spam[web]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let resolved = project_toml.resolve().unwrap();
+        let to_strings = |requirements: &[Requirement]| {
+            requirements
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        let windows = resolved.filter_for_markers(&environment(true));
+        let web = to_strings(&windows.optional_dependencies["web"]);
+        assert_eq!(web.len(), 2);
+        assert!(web[1].starts_with("django>2.0"));
+        let dev = to_strings(&windows.dependency_groups["dev"]);
+        assert_eq!(dev.len(), 3);
+        assert!(dev[0].starts_with("pywin32"));
+
+        let linux = resolved.filter_for_markers(&environment(false));
+        let web = to_strings(&linux.optional_dependencies["web"]);
+        assert_eq!(web.len(), 2);
+        assert!(web[1].starts_with("django>2.1"));
+        let dev = to_strings(&linux.dependency_groups["dev"]);
+        assert_eq!(dev.len(), 2);
+        assert_eq!(dev[0], "httpx");
+    }
+
+    #[test]
+    fn test_extras_marker_conflict() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+cpu = ["torch; sys_platform == 'win32'"]
+gpu = ["cupy; sys_platform != 'win32'"]
+docs = ["sphinx"]
+nt = ["pywin32; os_name == 'nt'"]
+mac = ["pyobjc; sys_platform == 'darwin'"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let project = project_toml.project.as_ref().unwrap();
+        let windows = environment(true);
+        let linux = environment(false);
+        assert!(project.extras_marker_conflict("cpu", "gpu", &windows));
+        assert!(project.extras_marker_conflict("cpu", "gpu", &linux));
+        // `docs` is active everywhere, so it can be installed together with `cpu`, even where
+        // `cpu` installs nothing
+        assert!(!project.extras_marker_conflict("docs", "cpu", &windows));
+        assert!(!project.extras_marker_conflict("docs", "cpu", &linux));
+        assert!(!project.extras_marker_conflict("docs", "gpu", &linux));
+        // Both can be active on Windows
+        assert!(!project.extras_marker_conflict("cpu", "nt", &windows));
+        assert!(!project.extras_marker_conflict("cpu", "nt", &linux));
+        // Exclusive, but neither installs anything on Linux
+        assert!(project.extras_marker_conflict("cpu", "mac", &windows));
+        assert!(!project.extras_marker_conflict("cpu", "mac", &linux));
+        assert!(!project.extras_marker_conflict("cpu", "missing", &windows));
+    }
+
+    #[test]
+    fn test_install_plan() {
+        let source = r#"[project]
+name = "spam"
+dependencies = ["httpx", "pywin32; sys_platform == 'win32'"]
+
+[project.optional-dependencies]
+test = ["pytest", "uvloop; sys_platform != 'win32'"]
+
+[dependency-groups]
+dev = ["ruff", "httpx", "spam[test]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let plan = |windows: bool| {
+            project_toml
+                .install_plan(&["test"], &["dev"], &environment(windows))
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            plan(false),
+            vec![
+                "httpx".to_string(),
+                "pytest".to_string(),
+                Requirement::<VerbatimUrl>::from_str("uvloop; sys_platform != 'win32'")
+                    .unwrap()
+                    .to_string(),
+                "ruff".to_string(),
+            ]
+        );
+        assert_eq!(
+            plan(true),
+            vec![
+                "httpx".to_string(),
+                Requirement::<VerbatimUrl>::from_str("pywin32; sys_platform == 'win32'")
+                    .unwrap()
+                    .to_string(),
+                "pytest".to_string(),
+                "ruff".to_string(),
+            ]
+        );
+        assert_eq!(
+            project_toml
+                .install_plan(&["docs"], &[], &environment(false))
+                .unwrap_err()
+                .to_string(),
+            "Failed to find optional dependency `docs`"
+        );
+    }
+
+    #[test]
+    fn test_obsolete_python_markers() {
+        let source = r#"[project]
+name = "spam"
+requires-python = ">=3.9"
+dependencies = [
+  "importlib-metadata; python_version >= '3.6'",
+  "tomli; python_version >= '3.6' and sys_platform == 'win32'",
+  "exceptiongroup; python_version < '3.11'",
+  "typing-extensions; python_version >= '3.12'",
+  "pywin32; python_version >= '3.6' and (sys_platform == 'win32' or sys_platform == 'cygwin')",
+]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let project = project_toml.project.as_ref().unwrap();
+        let obsolete: Vec<(String, String)> = project
+            .obsolete_python_markers()
+            .into_iter()
+            .map(|(requirement, simplified)| (requirement.name.to_string(), simplified))
+            .collect();
+        assert_eq!(
+            obsolete,
+            vec![
+                (
+                    "importlib-metadata".to_string(),
+                    "importlib-metadata".to_string()
+                ),
+                (
+                    "tomli".to_string(),
+                    Requirement::<VerbatimUrl>::from_str("tomli; sys_platform == 'win32'")
+                        .unwrap()
+                        .to_string()
+                ),
+                (
+                    "pywin32".to_string(),
+                    Requirement::<VerbatimUrl>::from_str(
+                        "pywin32; sys_platform == 'win32' or sys_platform == 'cygwin'"
+                    )
+                    .unwrap()
+                    .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markers_per_target() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+gpu = [
+  "torch; sys_platform == 'linux'",
+  "nvidia-cudnn-cu12; sys_platform == 'linux'",
+  "torch-directml; sys_platform == 'win32'",
+]
+cpu = ["torch"]
+
+[dependency-groups]
+dev = [{include-group = "test"}]
+test = ["pytest", "pywin32; os_name == 'nt'"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let markers = project_toml.markers_per_target();
+        let marker = |marker: &str| {
+            Requirement::<VerbatimUrl>::from_str(&format!("dummy; {marker}"))
+                .unwrap()
+                .marker
+                .try_to_string()
+                .unwrap()
+        };
+        assert_eq!(
+            markers[&InstallTarget::Extra("gpu".to_string())],
+            vec![
+                marker("sys_platform == 'linux'"),
+                marker("sys_platform == 'win32'")
+            ]
+        );
+        assert!(markers[&InstallTarget::Extra("cpu".to_string())].is_empty());
+        assert!(markers[&InstallTarget::Group("dev".to_string())].is_empty());
+        assert_eq!(
+            markers[&InstallTarget::Group("test".to_string())],
+            vec![marker("os_name == 'nt'")]
+        );
+    }
+
+    #[test]
+    fn test_build_python_markers() {
+        let source = r#"[build-system]
+requires = [
+  "setuptools>=61",
+  "tomli; python_version < '3.11'",
+  "wheel; python_version < '3.11' and sys_platform == 'win32'",
+]
+build-backend = "setuptools.build_meta"
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let expected = Requirement::<VerbatimUrl>::from_str("dummy; python_version < '3.11'")
+            .unwrap()
+            .marker
+            .try_to_string()
+            .unwrap();
+        assert_eq!(project_toml.build_python_markers(), vec![expected]);
+    }
+}
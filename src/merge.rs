@@ -0,0 +1,197 @@
+//! Merging a `pyproject.toml` into another, e.g. a package-local one into a workspace base.
+
+use indexmap::IndexMap;
+
+use crate::{BuildSystem, DependencyGroups, Project, PyProjectToml};
+
+/// Replace `value` with `other` if `other` is set.
+fn merge_scalar<T: Clone>(value: &mut Option<T>, other: &Option<T>) {
+    if other.is_some() {
+        value.clone_from(other);
+    }
+}
+
+/// Append the entries of `other` to `value`.
+fn merge_list<T: Clone>(value: &mut Option<Vec<T>>, other: &Option<Vec<T>>) {
+    if let Some(other) = other {
+        value
+            .get_or_insert_with(Vec::new)
+            .extend(other.iter().cloned());
+    }
+}
+
+/// Insert the entries of `other` into `value`, replacing the values of existing keys in place.
+fn merge_map<V: Clone>(value: &mut IndexMap<String, V>, other: &IndexMap<String, V>) {
+    value.extend(
+        other
+            .iter()
+            .map(|(key, entry)| (key.clone(), entry.clone())),
+    );
+}
+
+impl BuildSystem {
+    fn merge(&mut self, other: &BuildSystem) {
+        self.requires.extend(other.requires.iter().cloned());
+        merge_scalar(&mut self.build_backend, &other.build_backend);
+        merge_list(&mut self.backend_path, &other.backend_path);
+    }
+}
+
+impl Project {
+    fn merge(&mut self, other: &Project) {
+        self.name.clone_from(&other.name);
+        merge_scalar(&mut self.version, &other.version);
+        merge_scalar(&mut self.description, &other.description);
+        merge_scalar(&mut self.readme, &other.readme);
+        merge_scalar(&mut self.requires_python, &other.requires_python);
+        merge_scalar(&mut self.license, &other.license);
+        merge_list(&mut self.license_files, &other.license_files);
+        merge_list(&mut self.authors, &other.authors);
+        merge_list(&mut self.maintainers, &other.maintainers);
+        merge_list(&mut self.keywords, &other.keywords);
+        merge_list(&mut self.classifiers, &other.classifiers);
+        if let Some(urls) = &other.urls {
+            merge_map(self.urls.get_or_insert_with(IndexMap::new), urls);
+        }
+        for (group, entry_points) in other.entry_points.iter().flatten() {
+            merge_map(
+                self.entry_points
+                    .get_or_insert_with(IndexMap::new)
+                    .entry(group.clone())
+                    .or_default(),
+                entry_points,
+            );
+        }
+        if let Some(scripts) = &other.scripts {
+            merge_map(self.scripts.get_or_insert_with(IndexMap::new), scripts);
+        }
+        if let Some(gui_scripts) = &other.gui_scripts {
+            merge_map(
+                self.gui_scripts.get_or_insert_with(IndexMap::new),
+                gui_scripts,
+            );
+        }
+        merge_list(&mut self.dependencies, &other.dependencies);
+        if let Some(optional_dependencies) = &other.optional_dependencies {
+            merge_map(
+                self.optional_dependencies.get_or_insert_with(IndexMap::new),
+                optional_dependencies,
+            );
+        }
+        merge_list(&mut self.dynamic, &other.dynamic);
+    }
+}
+
+impl PyProjectToml {
+    /// Merge `other` into this `pyproject.toml`, with `other` taking precedence, e.g. to layer a
+    /// package-local configuration over a shared base.
+    ///
+    /// * Scalar fields set in `other`, such as `project.version` or `build-system.build-backend`,
+    ///   replace those in `self`. `project.name` is always taken from `other`.
+    /// * Lists, such as `project.dependencies` or `build-system.requires`, are concatenated, with
+    ///   the entries of `other` last. Duplicates are kept.
+    /// * Maps, such as `project.urls`, `project.scripts` or `project.optional-dependencies`,
+    ///   merge key by key, with the values of `other` replacing those of `self`. The groups in
+    ///   `project.entry-points` merge the same way, entry by entry.
+    /// * Dependency groups merge group by group, a group in `other` replaces the group of the same
+    ///   name in `self`.
+    /// * `[tool]` merges by tool, the table of a tool in `other` replaces the one in `self`.
+    ///
+    /// Keys are compared as written, without normalization.
+    pub fn merge(&mut self, other: &PyProjectToml) {
+        if let Some(other) = &other.build_system {
+            match &mut self.build_system {
+                Some(build_system) => build_system.merge(other),
+                None => self.build_system = Some(other.clone()),
+            }
+        }
+        if let Some(other) = &other.project {
+            match &mut self.project {
+                Some(project) => project.merge(other),
+                None => self.project = Some(other.clone()),
+            }
+        }
+        if let Some(other) = &other.dependency_groups {
+            merge_map(
+                &mut self
+                    .dependency_groups
+                    .get_or_insert_with(|| DependencyGroups(IndexMap::new()))
+                    .0,
+                other,
+            );
+        }
+        if let Some(other) = &other.tool {
+            merge_map(self.tool.get_or_insert_with(IndexMap::new), other);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PyProjectToml;
+
+    #[test]
+    fn test_merge() {
+        let mut base = PyProjectToml::new(
+            r#"[project]
+name = "base"
+version = "1.0"
+description = "The workspace"
+classifiers = ["Programming Language :: Python"]
+dependencies = ["httpx"]
+
+[project.urls]
+homepage = "https://example.com"
+repository = "https://github.com/example/workspace"
+
+[project.optional-dependencies]
+test = ["pytest"]
+
+[dependency-groups]
+dev = ["ruff"]
+"#,
+        )
+        .unwrap();
+        let package = PyProjectToml::new(
+            r#"[project]
+name = "spam"
+version = "2.0"
+dependencies = ["rich"]
+
+[project.urls]
+homepage = "https://spam.example.com"
+
+[project.optional-dependencies]
+cli = ["click"]
+
+[dependency-groups]
+dev = ["mypy"]
+"#,
+        )
+        .unwrap();
+        base.merge(&package);
+
+        let expected = PyProjectToml::new(
+            r#"[project]
+name = "spam"
+version = "2.0"
+description = "The workspace"
+classifiers = ["Programming Language :: Python"]
+dependencies = ["httpx", "rich"]
+
+[project.urls]
+homepage = "https://spam.example.com"
+repository = "https://github.com/example/workspace"
+
+[project.optional-dependencies]
+test = ["pytest"]
+cli = ["click"]
+
+[dependency-groups]
+dev = ["mypy"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(base, expected);
+    }
+}
@@ -0,0 +1,158 @@
+//! Writing the core metadata (`METADATA` and `PKG-INFO`) of a project.
+
+use pep508_rs::InvalidNameError;
+use thiserror::Error;
+
+use crate::{Contact, Project, ResolvedDependencies};
+
+/// An error writing core metadata, returned by [`Project::to_core_metadata`]
+#[derive(Debug, Error)]
+pub enum MetadataError {
+    #[error(transparent)]
+    Name(#[from] InvalidNameError),
+    #[error("Core metadata requires a version, but `project.version` is not set")]
+    MissingVersion,
+}
+
+impl Project {
+    /// Render the core metadata 2.3 of the project, e.g. for the `METADATA` file of a wheel.
+    ///
+    /// `resolved` provides the requirements of the extras, with self-references expanded, see
+    /// [`crate::PyProjectToml::resolve`]. Fields listed in `dynamic` are skipped, since the build
+    /// backend fills them in. The version is required even if it is dynamic, the backend has to
+    /// set it first.
+    pub fn to_core_metadata(
+        &self,
+        resolved: &ResolvedDependencies,
+    ) -> Result<String, MetadataError> {
+        self.normalized_name()?;
+        let version = self.version.as_ref().ok_or(MetadataError::MissingVersion)?;
+
+        let mut lines = vec![
+            "Metadata-Version: 2.3".to_string(),
+            format!("Name: {}", self.name),
+            format!("Version: {version}"),
+        ];
+        if let Some(description) = self.static_field("description", &self.description) {
+            lines.push(format!("Summary: {description}"));
+        }
+        if let Some(authors) = self.static_field("authors", &self.authors) {
+            let (names, emails): (Vec<&Contact>, Vec<&Contact>) = authors
+                .iter()
+                .partition(|author| matches!(author, Contact::Name { .. }));
+            if !names.is_empty() {
+                let names: Vec<String> = names.iter().map(ToString::to_string).collect();
+                lines.push(format!("Author: {}", names.join(", ")));
+            }
+            if !emails.is_empty() {
+                let emails: Vec<String> = emails
+                    .iter()
+                    .map(|author| match author {
+                        // A bare address is written without angle brackets
+                        Contact::Email { email } => email.clone(),
+                        author => author.to_string(),
+                    })
+                    .collect();
+                lines.push(format!("Author-email: {}", emails.join(", ")));
+            }
+        }
+        for classifier in self
+            .static_field("classifiers", &self.classifiers)
+            .into_iter()
+            .flatten()
+        {
+            lines.push(format!("Classifier: {classifier}"));
+        }
+        if let Some(requires_python) = self.static_field("requires-python", &self.requires_python) {
+            lines.push(format!("Requires-Python: {requires_python}"));
+        }
+        for (label, url) in self.static_field("urls", &self.urls).into_iter().flatten() {
+            lines.push(format!("Project-URL: {label}, {url}"));
+        }
+        for requirement in self
+            .static_field("dependencies", &self.dependencies)
+            .into_iter()
+            .flatten()
+        {
+            lines.push(format!("Requires-Dist: {requirement}"));
+        }
+        if !self.is_dynamic("optional-dependencies") {
+            lines.extend(resolved.requires_dist_lines());
+            for extra in resolved.optional_dependencies.keys() {
+                lines.push(format!("Provides-Extra: {extra}"));
+            }
+        }
+
+        let mut metadata = lines.join("\n");
+        metadata.push('\n');
+        Ok(metadata)
+    }
+
+    /// The value of a field, unless it is listed in `dynamic`.
+    fn static_field<'a, T>(&self, field: &str, value: &'a Option<T>) -> Option<&'a T> {
+        if self.is_dynamic(field) {
+            None
+        } else {
+            value.as_ref()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+
+    use crate::{MetadataError, PyProjectToml};
+
+    #[test]
+    fn test_to_core_metadata() {
+        let source = r#"[project]
+name = "spam"
+version = "2020.0.0"
+description = "Lovely Spam! Wonderful Spam!"
+requires-python = ">=3.8"
+authors = [
+  {name = "Pradyun Gedam"},
+  {email = "tzu-ping@example.com"},
+  {name = "Brett Cannon", email = "brett@python.org"},
+]
+classifiers = ["Programming Language :: Python"]
+dependencies = ["httpx", "gidgethub[httpx]>4.0.0"]
+dynamic = ["urls"]
+
+[project.optional-dependencies]
+test = ["pytest<5.0.0", "spam[cli]"]
+cli = ["click"]
+
+[project.urls]
+homepage = "https://example.com"
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let project = project_toml.project.as_ref().unwrap();
+        let resolved = project_toml.resolve().unwrap();
+        assert_snapshot!(project.to_core_metadata(&resolved).unwrap(), @r#"
+        Metadata-Version: 2.3
+        Name: spam
+        Version: 2020.0.0
+        Summary: Lovely Spam! Wonderful Spam!
+        Author: Pradyun Gedam
+        Author-email: tzu-ping@example.com, Brett Cannon <brett@python.org>
+        Classifier: Programming Language :: Python
+        Requires-Python: >=3.8
+        Requires-Dist: httpx
+        Requires-Dist: gidgethub[httpx]>4.0.0
+        Requires-Dist: pytest<5.0.0 ; extra == 'test'
+        Requires-Dist: click ; extra == 'test'
+        Requires-Dist: click ; extra == 'cli'
+        Provides-Extra: test
+        Provides-Extra: cli
+        "#);
+
+        let mut project = project.clone();
+        project.version = None;
+        assert!(matches!(
+            project.to_core_metadata(&resolved),
+            Err(MetadataError::MissingVersion)
+        ));
+    }
+}
@@ -0,0 +1,169 @@
+//! Checks on the project name and the dependency group names.
+
+use indexmap::IndexMap;
+use pep508_rs::{ExtraName, InvalidNameError};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::resolution::normalize_name;
+use crate::{DependencyGroups, Project};
+
+/// An invalid `[dependency-groups]` name, returned by [`DependencyGroups::validate_names`]
+#[derive(Debug, Error)]
+pub enum GroupNameError {
+    #[error("`{name}` is not a valid dependency group name")]
+    InvalidName {
+        name: String,
+        #[source]
+        err: InvalidNameError,
+    },
+    #[error(
+        "The dependency groups `{first}` and `{second}` are the same group after normalization"
+    )]
+    Collision { first: String, second: String },
+}
+
+/// A project name that is valid, but likely to be rejected or confusing on upload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameWarning {
+    /// The name contains a run of separators, e.g. `foo__bar`
+    ConsecutiveSeparators,
+    /// The name starts or ends with a separator, e.g. `_foo`
+    LeadingOrTrailingSeparator,
+    /// The normalized name has only a single character
+    TooShort {
+        /// The normalized name
+        normalized: String,
+    },
+}
+
+impl Display for NameWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NameWarning::ConsecutiveSeparators => {
+                write!(f, "The name contains consecutive separators")
+            }
+            NameWarning::LeadingOrTrailingSeparator => {
+                write!(f, "The name starts or ends with a separator")
+            }
+            NameWarning::TooShort { normalized } => {
+                write!(f, "The name normalizes to the very short `{normalized}`")
+            }
+        }
+    }
+}
+
+fn is_separator(c: char) -> bool {
+    matches!(c, '-' | '_' | '.')
+}
+
+impl Project {
+    /// Return advisory warnings about the project name, such as `foo__bar` containing consecutive
+    /// separators.
+    ///
+    /// These don't make the name invalid, but package indexes may reject them on upload.
+    pub fn name_warnings(&self) -> Vec<NameWarning> {
+        let mut warnings = Vec::new();
+        let chars: Vec<char> = self.name.chars().collect();
+        if chars
+            .windows(2)
+            .any(|pair| is_separator(pair[0]) && is_separator(pair[1]))
+        {
+            warnings.push(NameWarning::ConsecutiveSeparators);
+        }
+        if self.name.starts_with(is_separator) || self.name.ends_with(is_separator) {
+            warnings.push(NameWarning::LeadingOrTrailingSeparator);
+        }
+        let normalized = normalize_name(&self.name);
+        if normalized.chars().count() <= 1 {
+            warnings.push(NameWarning::TooShort { normalized });
+        }
+        warnings
+    }
+}
+
+impl DependencyGroups {
+    /// Check that all group names are valid names as PEP 735 requires, i.e. ASCII letters, digits,
+    /// `-`, `_` and `.`, starting and ending with a letter or digit, and that no two names are the
+    /// same after normalization, e.g. `test_group` and `test-group`.
+    ///
+    /// Returns an error for the first invalid name or collision.
+    pub fn validate_names(&self) -> Result<(), GroupNameError> {
+        let mut names: IndexMap<ExtraName, &str> = IndexMap::new();
+        for group in self.keys() {
+            let normalized =
+                ExtraName::from_str(group).map_err(|err| GroupNameError::InvalidName {
+                    name: group.clone(),
+                    err,
+                })?;
+            if let Some(first) = names.insert(normalized, group) {
+                return Err(GroupNameError::Collision {
+                    first: first.to_string(),
+                    second: group.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NameWarning;
+    use crate::{Project, PyProjectToml};
+
+    #[test]
+    fn test_name_warnings() {
+        let project = Project::new("foo__bar".to_string());
+        assert_eq!(
+            project.name_warnings(),
+            vec![NameWarning::ConsecutiveSeparators]
+        );
+
+        let project = Project::new("_foo".to_string());
+        assert_eq!(
+            project.name_warnings(),
+            vec![NameWarning::LeadingOrTrailingSeparator]
+        );
+
+        let project = Project::new("X".to_string());
+        assert_eq!(
+            project.name_warnings(),
+            vec![NameWarning::TooShort {
+                normalized: "x".to_string()
+            }]
+        );
+
+        assert!(Project::new("foo-bar".to_string())
+            .name_warnings()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_validate_group_names() {
+        let groups = |source: &str| {
+            PyProjectToml::new(source)
+                .unwrap()
+                .dependency_groups
+                .unwrap()
+        };
+        groups("[dependency-groups]\ntest-group = []\n3d = []\n")
+            .validate_names()
+            .unwrap();
+        assert_eq!(
+            groups("[dependency-groups]\n\"test group\" = []\n")
+                .validate_names()
+                .unwrap_err()
+                .to_string(),
+            "`test group` is not a valid dependency group name"
+        );
+        assert_eq!(
+            groups("[dependency-groups]\ntest_group = []\n\"Test.Group\" = []\n")
+                .validate_names()
+                .unwrap_err()
+                .to_string(),
+            "The dependency groups `test_group` and `Test.Group` are the same group after normalization"
+        );
+    }
+}
@@ -1,6 +1,10 @@
 //! Implementation of PEP 639 cross-language restricted globs.
 
-use glob::{Pattern, PatternError};
+use glob::{MatchOptions, Pattern, PatternError};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -9,10 +13,18 @@ pub enum Pep639GlobError {
     PatternError(#[from] PatternError),
     #[error("The parent directory operator (`..`) at position {pos} is not allowed in license file globs")]
     ParentDirectory { pos: usize },
+    #[error("License file path `{}` must be relative to the project root", path.display())]
+    AbsolutePath { path: PathBuf },
     #[error("Glob contains invalid character at position {pos}: `{invalid}`")]
     InvalidCharacter { pos: usize, invalid: char },
     #[error("Glob contains invalid character in range at position {pos}: `{invalid}`")]
     InvalidCharacterRange { pos: usize, invalid: char },
+    #[error("Failed to read license files from `{}`", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        err: io::Error,
+    },
 }
 
 /// Parse a PEP 639 `license-files` glob
@@ -77,6 +89,106 @@ pub fn parse_pep639_glob(glob: &str) -> Result<Pattern, Pep639GlobError> {
     Ok(Pattern::new(glob)?)
 }
 
+/// Find the files matching any of the PEP 639 `license-files` globs, as sorted paths relative to
+/// `root`, the directory containing `pyproject.toml`.
+///
+/// Symlinks are only matched if they point to a file inside `root`, and symlinked directories
+/// are not descended into.
+pub fn resolve_license_files(
+    root: &Path,
+    patterns: &[String],
+) -> Result<Vec<PathBuf>, Pep639GlobError> {
+    let patterns = patterns
+        .iter()
+        .map(|pattern| parse_pep639_glob(pattern))
+        .collect::<Result<Vec<_>, _>>()?;
+    let io_err = |path: &Path| {
+        let path = path.to_path_buf();
+        move |err| Pep639GlobError::Io { path, err }
+    };
+    let canonical_root = root.canonicalize().map_err(io_err(root))?;
+    let options = MatchOptions {
+        case_sensitive: true,
+        require_literal_separator: true,
+        require_literal_leading_dot: false,
+    };
+
+    let mut matches = BTreeSet::new();
+    let mut directories = vec![root.to_path_buf()];
+    while let Some(directory) = directories.pop() {
+        for entry in fs::read_dir(&directory).map_err(io_err(&directory))? {
+            let path = entry.map_err(io_err(&directory))?.path();
+            let metadata = fs::symlink_metadata(&path).map_err(io_err(&path))?;
+            if metadata.is_dir() {
+                directories.push(path);
+                continue;
+            }
+            if metadata.file_type().is_symlink() {
+                // Only follow symlinks to files that stay inside the project
+                let Ok(target) = path.canonicalize() else {
+                    continue;
+                };
+                if !target.starts_with(&canonical_root) || !target.is_file() {
+                    continue;
+                }
+            }
+            let Ok(relative) = path.strip_prefix(root) else {
+                continue;
+            };
+            if relative
+                .components()
+                .any(|component| !matches!(component, Component::Normal(_)))
+            {
+                continue;
+            }
+            let Some(relative_str) = relative.to_str() else {
+                continue;
+            };
+            let relative_str = relative_str.replace(std::path::MAIN_SEPARATOR, "/");
+            if patterns
+                .iter()
+                .any(|pattern| pattern.matches_with(&relative_str, options))
+            {
+                matches.insert(relative.to_path_buf());
+            }
+        }
+    }
+    Ok(matches.into_iter().collect())
+}
+
+/// Write a license file path as PEP 639 requires in core metadata, i.e. relative to the project
+/// root, with forward slashes and without a leading `./`.
+///
+/// Backslashes are read as path separators regardless of the platform, e.g. `.\vendor\LICENSE`
+/// becomes `vendor/LICENSE`. Absolute paths and paths containing `..` can't be expressed relative
+/// to the project root and are rejected.
+pub fn normalize_license_path(path: &Path) -> Result<String, Pep639GlobError> {
+    let given = path.to_string_lossy();
+    // A leading separator or a drive such as `C:` make the path absolute on some platform
+    let drive = given
+        .split(['/', '\\'])
+        .next()
+        .map_or(false, |first| first.contains(':'));
+    if path.has_root() || given.starts_with(['/', '\\']) || drive {
+        return Err(Pep639GlobError::AbsolutePath {
+            path: path.to_path_buf(),
+        });
+    }
+
+    let mut components = Vec::new();
+    let mut pos = 0;
+    for component in given.split(['/', '\\']) {
+        if component == ".." {
+            return Err(Pep639GlobError::ParentDirectory { pos });
+        }
+        if !component.is_empty() && component != "." {
+            components.push(component);
+        }
+        pos += component.len() + 1;
+    }
+    Ok(components.join("/"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -133,4 +245,83 @@ mod tests {
             parse_pep639_glob(case).unwrap();
         }
     }
+
+    #[test]
+    fn test_resolve_license_files() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir(root.path().join("vendor")).unwrap();
+        for file in ["LICENSE", "LICENSE.md", "README.md", "vendor/LICENSE"] {
+            fs::write(root.path().join(file), "").unwrap();
+        }
+        // A symlink to a file outside the project is never matched
+        #[cfg(unix)]
+        let outside = tempfile::tempdir().unwrap();
+        #[cfg(unix)]
+        {
+            fs::write(outside.path().join("LICENSE"), "").unwrap();
+            std::os::unix::fs::symlink(
+                outside.path().join("LICENSE"),
+                root.path().join("LICENSE.outside"),
+            )
+            .unwrap();
+        }
+
+        let patterns = |patterns: &[&str]| {
+            let patterns: Vec<String> = patterns.iter().map(ToString::to_string).collect();
+            resolve_license_files(root.path(), &patterns)
+        };
+        assert_eq!(
+            patterns(&["LICENSE*", "**/LICENSE"]).unwrap(),
+            [
+                PathBuf::from("LICENSE"),
+                PathBuf::from("LICENSE.md"),
+                PathBuf::from("vendor/LICENSE")
+            ]
+        );
+        assert_eq!(
+            patterns(&["*/LICENSE"]).unwrap(),
+            [PathBuf::from("vendor/LICENSE")]
+        );
+        assert!(matches!(
+            patterns(&["../LICENSE"]),
+            Err(Pep639GlobError::ParentDirectory { pos: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_normalize_license_path() {
+        for (path, normalized) in [
+            ("LICENSE", "LICENSE"),
+            ("vendor/LICENSE", "vendor/LICENSE"),
+            ("vendor\\LICENSE", "vendor/LICENSE"),
+            ("./LICENSE", "LICENSE"),
+            (".\\vendor\\licenses/MIT.txt", "vendor/licenses/MIT.txt"),
+            ("vendor/./LICENSE", "vendor/LICENSE"),
+        ] {
+            assert_eq!(normalize_license_path(Path::new(path)).unwrap(), normalized);
+        }
+
+        for path in [
+            "/etc/LICENSE",
+            "\\\\server\\LICENSE",
+            "C:\\LICENSE",
+            "C:LICENSE",
+        ] {
+            assert!(
+                matches!(
+                    normalize_license_path(Path::new(path)),
+                    Err(Pep639GlobError::AbsolutePath { .. })
+                ),
+                "{path}"
+            );
+        }
+        assert!(matches!(
+            normalize_license_path(Path::new("vendor/../LICENSE")),
+            Err(Pep639GlobError::ParentDirectory { pos: 7 })
+        ));
+        assert!(matches!(
+            normalize_license_path(Path::new("..\\LICENSE")),
+            Err(Pep639GlobError::ParentDirectory { pos: 0 })
+        ));
+    }
 }
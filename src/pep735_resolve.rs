@@ -2,14 +2,28 @@ use indexmap::IndexMap;
 use pep508_rs::Requirement;
 use thiserror::Error;
 
-use crate::{DependencyGroupSpecifier, DependencyGroups};
+use crate::{DependencyGroupSpecifier, DependencyGroups, ResolveError};
 
 #[derive(Debug, Error)]
 pub enum Pep735Error {
     #[error("Failed to find group `{0}` included by `{1}`")]
     GroupNotFound(String, String),
+    #[error("Group `{0}` does not exist")]
+    UnknownGroup(String),
     #[error("Detected a cycle in `dependency-groups`: {0}")]
     DependencyGroupCycle(Cycle),
+    /// An entry that can only be resolved with the whole `pyproject.toml`, see
+    /// [`crate::PyProjectToml::resolve`]
+    #[error(transparent)]
+    Resolve(ResolveError),
+    /// A group includes an extra with `{ include-extra = "..." }`, which only the project-aware
+    /// [`crate::PyProjectToml::resolve`] can resolve
+    #[cfg(feature = "include-extra")]
+    #[error(
+        "Group `{0}` includes the extra `{1}`, which needs the project-aware resolver \
+        `PyProjectToml::resolve`"
+    )]
+    IncludeExtra(String, String),
 }
 
 /// A cycle in the `dependency-groups` table.
@@ -31,66 +45,129 @@ impl std::fmt::Display for Cycle {
     }
 }
 
-impl DependencyGroups {
-    /// Resolve dependency groups (which may contain references to other groups) into concrete
-    /// lists of requirements.
-    pub fn resolve(&self) -> Result<IndexMap<String, Vec<Requirement>>, Pep735Error> {
-        // Helper function to resolves a single group
-        fn resolve_single<'a>(
-            groups: &'a DependencyGroups,
-            group: &'a str,
-            resolved: &mut IndexMap<String, Vec<Requirement>>,
-            parents: &mut Vec<&'a str>,
-        ) -> Result<(), Pep735Error> {
-            let Some(specifiers) = groups.get(group) else {
-                // If the group included in another group does not exist, return an error
-                let parent = parents.iter().last().expect("should have a parent");
-                return Err(Pep735Error::GroupNotFound(
-                    group.to_string(),
-                    parent.to_string(),
-                ));
-            };
-            // If there is a cycle in dependency groups, return an error
-            if parents.contains(&group) {
-                return Err(Pep735Error::DependencyGroupCycle(Cycle(
-                    parents.iter().map(|s| s.to_string()).collect(),
-                )));
-            }
-            // If the dependency group has already been resolved, exit early
-            if resolved.get(group).is_some() {
-                return Ok(());
+/// Resolve a single group into `resolved`, after the groups it includes.
+///
+/// The requirements are borrowed from `groups`, so that each is only cloned once for the output.
+fn resolve_single<'a>(
+    groups: &'a DependencyGroups,
+    group: &'a str,
+    resolved: &mut IndexMap<&'a str, Vec<&'a Requirement>>,
+    parents: &mut Vec<&'a str>,
+) -> Result<(), Pep735Error> {
+    let Some(specifiers) = groups.get(group) else {
+        // If the group included in another group does not exist, return an error
+        let parent = parents.iter().last().expect("should have a parent");
+        return Err(Pep735Error::GroupNotFound(
+            group.to_string(),
+            parent.to_string(),
+        ));
+    };
+    // If there is a cycle in dependency groups, return an error
+    if parents.contains(&group) {
+        return Err(Pep735Error::DependencyGroupCycle(Cycle(
+            parents.iter().map(|s| s.to_string()).collect(),
+        )));
+    }
+    // If the dependency group has already been resolved, exit early
+    if resolved.get(group).is_some() {
+        return Ok(());
+    }
+    // Otherwise, perform recursion, as required, on the dependency group's specifiers
+    parents.push(group);
+    let mut requirements = Vec::with_capacity(specifiers.len());
+    for spec in specifiers.iter() {
+        match spec {
+            // It's a requirement. Just add it to the Vec of resolved requirements
+            DependencyGroupSpecifier::String(requirement) => requirements.push(requirement),
+            // It's a reference to another group. Recurse into it
+            DependencyGroupSpecifier::Table { include_group } => {
+                resolve_single(groups, include_group, resolved, parents)?;
+                requirements.extend(resolved.get(include_group.as_str()).into_iter().flatten());
             }
-            // Otherwise, perform recursion, as required, on the dependency group's specifiers
-            parents.push(group);
-            let mut requirements = Vec::with_capacity(specifiers.len());
-            for spec in specifiers.iter() {
-                match spec {
-                    // It's a requirement. Just add it to the Vec of resolved requirements
-                    DependencyGroupSpecifier::String(requirement) => {
-                        requirements.push(requirement.clone())
-                    }
-                    // It's a reference to another group. Recurse into it
-                    DependencyGroupSpecifier::Table { include_group } => {
-                        resolve_single(groups, include_group, resolved, parents)?;
-                        requirements
-                            .extend(resolved.get(include_group).into_iter().flatten().cloned());
+            // It's a requirements file, which can't be read without the project root
+            #[cfg(feature = "requirements-txt")]
+            DependencyGroupSpecifier::Path { path } => {
+                return Err(Pep735Error::Resolve(
+                    crate::ResolveErrorKind::RequirementsFileWithoutRoot {
+                        group: group.to_string(),
+                        path: path.clone(),
                     }
-                }
+                    .into(),
+                ));
+            }
+            // It's an extra, which is not part of the groups table
+            #[cfg(feature = "include-extra")]
+            DependencyGroupSpecifier::IncludeExtra { include_extra } => {
+                return Err(Pep735Error::IncludeExtra(
+                    group.to_string(),
+                    include_extra.clone(),
+                ));
             }
-            // Add the resolved group to IndexMap
-            resolved.insert(group.to_string(), requirements.clone());
-            parents.pop();
-            Ok(())
         }
+    }
+    // Add the resolved group to IndexMap
+    resolved.insert(group, requirements);
+    parents.pop();
+    Ok(())
+}
 
+impl DependencyGroups {
+    /// Resolve dependency groups (which may contain references to other groups) into concrete
+    /// lists of requirements.
+    ///
+    /// Requirements files included with `{ path = "..." }` are an error, they can only be read
+    /// by `PyProjectToml::resolve_with_root`. So are `{ include-extra = "..." }` entries, which
+    /// need the project-aware `PyProjectToml::resolve` since the extras are not part of the groups
+    /// table.
+    pub fn resolve(&self) -> Result<IndexMap<String, Vec<Requirement>>, Pep735Error> {
         let mut resolved = IndexMap::new();
         for group in self.keys() {
             resolve_single(self, group, &mut resolved, &mut Vec::new())?;
         }
-        Ok(resolved)
+        Ok(resolved
+            .into_iter()
+            .map(|(group, requirements)| (group.to_string(), to_owned(requirements)))
+            .collect())
+    }
+
+    /// Like [`DependencyGroups::resolve`], but the output lists the groups in the order they are
+    /// declared in, rather than the order they were resolved in.
+    ///
+    /// With a `name`, only that group and the groups it includes, directly or transitively, are
+    /// resolved.
+    pub fn resolve_ordered(
+        &self,
+        name: Option<&str>,
+    ) -> Result<IndexMap<String, Vec<Requirement>>, Pep735Error> {
+        let mut resolved = IndexMap::new();
+        match name {
+            Some(name) => {
+                if !self.contains_key(name) {
+                    return Err(Pep735Error::UnknownGroup(name.to_string()));
+                }
+                resolve_single(self, name, &mut resolved, &mut Vec::new())?;
+            }
+            None => {
+                for group in self.keys() {
+                    resolve_single(self, group, &mut resolved, &mut Vec::new())?;
+                }
+            }
+        }
+        Ok(self
+            .keys()
+            .filter_map(|group| {
+                let requirements = resolved.swap_remove(group.as_str())?;
+                Some((group.clone(), to_owned(requirements)))
+            })
+            .collect())
     }
 }
 
+/// Clone the borrowed requirements of a resolved group for the output.
+fn to_owned(requirements: Vec<&Requirement>) -> Vec<Requirement> {
+    requirements.into_iter().cloned().collect()
+}
+
 #[cfg(test)]
 mod tests {
     use pep508_rs::Requirement;
@@ -118,6 +195,40 @@ iota = [{include-group = "alpha"}]
         );
     }
 
+    #[test]
+    fn test_resolve_ordered() {
+        let source = r#"[dependency-groups]
+dev = [{include-group = "test"}, {include-group = "lint"}]
+lint = ["ruff"]
+docs = ["sphinx"]
+test = ["pytest"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let dependency_groups = project_toml.dependency_groups.as_ref().unwrap();
+
+        let resolved = dependency_groups.resolve_ordered(None).unwrap();
+        assert_eq!(
+            resolved.keys().collect::<Vec<_>>(),
+            ["dev", "lint", "docs", "test"]
+        );
+        let resolved = dependency_groups.resolve_ordered(Some("dev")).unwrap();
+        assert_eq!(resolved.keys().collect::<Vec<_>>(), ["dev", "lint", "test"]);
+        assert_eq!(
+            resolved["dev"],
+            vec![
+                Requirement::from_str("pytest").unwrap(),
+                Requirement::from_str("ruff").unwrap()
+            ]
+        );
+        assert_eq!(
+            dependency_groups
+                .resolve_ordered(Some("typing"))
+                .unwrap_err()
+                .to_string(),
+            "Group `typing` does not exist"
+        );
+    }
+
     #[test]
     fn test_parse_pyproject_toml_dependency_groups_cycle() {
         let source = r#"[dependency-groups]
@@ -144,4 +255,27 @@ iota = [{include-group = "alpha"}]
             String::from("Failed to find group `alpha` included by `iota`")
         )
     }
+
+    #[test]
+    #[cfg(feature = "include-extra")]
+    fn test_parse_pyproject_toml_dependency_groups_include_extra() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+test = ["pytest"]
+
+[dependency-groups]
+dev = [{include-extra = "test"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let dependency_groups = project_toml.dependency_groups.as_ref().unwrap();
+        assert_eq!(
+            dependency_groups.resolve().unwrap_err().to_string(),
+            String::from(
+                "Group `dev` includes the extra `test`, which needs the project-aware resolver \
+                `PyProjectToml::resolve`"
+            )
+        )
+    }
 }
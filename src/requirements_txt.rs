@@ -0,0 +1,280 @@
+//! Reading the requirements files that dependency groups include with `{ path = "..." }`.
+
+use pep508_rs::Requirement;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::resolution::{ResolveError, ResolveErrorKind};
+
+/// Read the requirements from the `requirements.txt`-style file `path` that `group` includes,
+/// relative to `root`, following `-r` includes.
+///
+/// Supported are one requirement per line, `#` comments, line continuations with `\` and
+/// `-r <file>` or `--requirement <file>` includes relative to the including file. Other options
+/// such as `-e` or `--index-url` are rejected, since they can't be expressed as requirements.
+///
+/// The file and all files it includes must be inside `root` after resolving symlinks.
+pub(crate) fn read_requirements(
+    root: &Path,
+    group: &str,
+    path: &str,
+) -> Result<Vec<Requirement>, ResolveError> {
+    let root = root
+        .canonicalize()
+        .map_err(|err| ResolveErrorKind::RequirementsFileIo {
+            path: root.to_path_buf(),
+            err,
+        })?;
+    let file = root.join(path);
+    if !canonicalize(&file)?.starts_with(&root) {
+        return Err(ResolveErrorKind::RequirementsFileOutsideRoot {
+            group: group.to_string(),
+            path: path.to_string(),
+        }
+        .into());
+    }
+    let mut requirements = Vec::new();
+    read_into(&root, &file, &mut Vec::new(), &mut requirements)?;
+    Ok(requirements)
+}
+
+/// Resolve symlinks and `..` in `path`, which must exist.
+fn canonicalize(path: &Path) -> Result<PathBuf, ResolveError> {
+    path.canonicalize().map_err(|err| {
+        ResolveErrorKind::RequirementsFileIo {
+            path: path.to_path_buf(),
+            err,
+        }
+        .into()
+    })
+}
+
+/// Read `path` into `requirements`, where `root` is the canonical project root and `parents` are
+/// the files currently being read, as canonical and as given path.
+fn read_into(
+    root: &Path,
+    path: &Path,
+    parents: &mut Vec<(PathBuf, PathBuf)>,
+    requirements: &mut Vec<Requirement>,
+) -> Result<(), ResolveError> {
+    let canonical = canonicalize(path)?;
+    // If the file includes itself, return an error
+    if let Some(pos) = parents.iter().position(|(parent, _)| *parent == canonical) {
+        let cycle = parents[pos..]
+            .iter()
+            .map(|(_, given)| given.clone())
+            .collect();
+        return Err(ResolveErrorKind::RequirementsFileCycle(cycle).into());
+    }
+    let content =
+        std::fs::read_to_string(path).map_err(|err| ResolveErrorKind::RequirementsFileIo {
+            path: path.to_path_buf(),
+            err,
+        })?;
+
+    parents.push((canonical, path.to_path_buf()));
+    for (number, line) in logical_lines(&content) {
+        let syntax_err = |message: String| ResolveErrorKind::RequirementsFileSyntax {
+            path: path.to_path_buf(),
+            line: number,
+            message,
+        };
+        let include = line
+            .strip_prefix("-r")
+            .or_else(|| line.strip_prefix("--requirement"))
+            .map(|include| include.trim_start_matches('=').trim());
+        match include {
+            Some("") => return Err(syntax_err("missing file after `-r`".to_string()).into()),
+            Some(include) => {
+                let base = path.parent().unwrap_or_else(|| Path::new(""));
+                let included = base.join(include);
+                if !canonicalize(&included)?.starts_with(root) {
+                    return Err(ResolveErrorKind::RequirementsFileIncludeOutsideRoot {
+                        path: path.to_path_buf(),
+                        include: include.to_string(),
+                    }
+                    .into());
+                }
+                read_into(root, &included, parents, requirements)?;
+            }
+            None if line.starts_with('-') => {
+                return Err(syntax_err(format!("unsupported option `{line}`")).into());
+            }
+            None => {
+                let requirement =
+                    Requirement::from_str(&line).map_err(|err| syntax_err(err.to_string()))?;
+                requirements.push(requirement);
+            }
+        }
+    }
+    parents.pop();
+    Ok(())
+}
+
+/// Join continued lines and strip comments, returning the non-empty lines with the line number
+/// they start on.
+fn logical_lines(content: &str) -> Vec<(usize, String)> {
+    let mut lines = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+    for (index, line) in content.lines().enumerate() {
+        let (number, mut logical) = current.take().unwrap_or((index + 1, String::new()));
+        match line.strip_suffix('\\') {
+            Some(continued) => {
+                logical.push_str(continued);
+                current = Some((number, logical));
+                continue;
+            }
+            None => logical.push_str(line),
+        }
+        // A comment starts with `#` at the beginning of the line or after whitespace
+        let comment = logical
+            .char_indices()
+            .find(|&(pos, c)| {
+                c == '#' && (pos == 0 || logical[..pos].ends_with(char::is_whitespace))
+            })
+            .map(|(pos, _)| pos);
+        if let Some(pos) = comment {
+            logical.truncate(pos);
+        }
+        let logical = logical.trim();
+        if !logical.is_empty() {
+            lines.push((number, logical.to_string()));
+        }
+    }
+    if let Some((number, logical)) = current {
+        let logical = logical.trim();
+        if !logical.is_empty() {
+            lines.push((number, logical.to_string()));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::PyProjectToml;
+
+    #[test]
+    fn test_resolve_requirements_file() {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(
+            root.path().join("requirements.txt"),
+            "# Test dependencies\npytest>=8  # the test runner\nhypothesis \\\n  >=6\n",
+        )
+        .unwrap();
+        let source = r#"[project]
+name = "spam"
+
+[dependency-groups]
+test = [{path = "requirements.txt"}]
+dev = ["ruff", {include-group = "test"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let resolved = project_toml.resolve_with_root(root.path()).unwrap();
+        let dev: Vec<String> = resolved.dependency_groups["dev"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(dev, ["ruff", "pytest>=8", "hypothesis>=6"]);
+
+        assert_eq!(
+            project_toml.resolve().unwrap_err().to_string(),
+            "Dependency group `test` includes the file `requirements.txt`, which can only be read when resolving with a project root"
+        );
+    }
+
+    #[test]
+    fn test_resolve_requirements_file_outside_root() {
+        let root = tempfile::tempdir().unwrap();
+        for path in [
+            "../requirements.txt",
+            "ci/../../requirements.txt",
+            "/etc/requirements.txt",
+        ] {
+            let source = format!("[dependency-groups]\ndev = [{{path = \"{path}\"}}]\n");
+            let project_toml = PyProjectToml::new(&source).unwrap();
+            assert_eq!(
+                project_toml
+                    .resolve_with_root(root.path())
+                    .unwrap_err()
+                    .to_string(),
+                format!("Dependency group `dev` includes the file `{path}`, which is not a relative path inside the project root")
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_requirements_file_include_outside_root() {
+        let outer = tempfile::tempdir().unwrap();
+        let root = outer.path().join("project");
+        fs::create_dir_all(root.join("ci")).unwrap();
+        fs::write(outer.path().join("secret.txt"), "secret\n").unwrap();
+        fs::write(
+            root.join("ci").join("test.txt"),
+            "pytest\n-r ../../secret.txt\n",
+        )
+        .unwrap();
+        let source = r#"[dependency-groups]
+test = [{path = "ci/test.txt"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let root = root.canonicalize().unwrap();
+        assert_eq!(
+            project_toml.resolve_with_root(&root).unwrap_err().to_string(),
+            format!(
+                "Requirements file `{}` includes `../../secret.txt`, which is outside the project root",
+                root.join("ci/test.txt").display()
+            )
+        );
+
+        // A symlink can't point outside the root either
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(outer.path().join("secret.txt"), root.join("link.txt"))
+                .unwrap();
+            fs::write(root.join("ci").join("test.txt"), "-r ../link.txt\n").unwrap();
+            assert_eq!(
+                project_toml.resolve_with_root(&root).unwrap_err().to_string(),
+                format!(
+                    "Requirements file `{}` includes `../link.txt`, which is outside the project root",
+                    root.join("ci/test.txt").display()
+                )
+            );
+            let source = r#"[dependency-groups]
+test = [{path = "link.txt"}]
+"#;
+            let project_toml = PyProjectToml::new(source).unwrap();
+            assert_eq!(
+                project_toml.resolve_with_root(&root).unwrap_err().to_string(),
+                "Dependency group `test` includes the file `link.txt`, which is not a relative path inside the project root"
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_requirements_file_cycle() {
+        let root = tempfile::tempdir().unwrap();
+        fs::write(root.path().join("a.txt"), "httpx\n-r b.txt\n").unwrap();
+        fs::write(root.path().join("b.txt"), "--requirement a.txt\n").unwrap();
+        let source = r#"[dependency-groups]
+dev = [{path = "a.txt"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let a = root.path().canonicalize().unwrap().join("a.txt");
+        let b = root.path().canonicalize().unwrap().join("b.txt");
+        assert_eq!(
+            project_toml
+                .resolve_with_root(root.path())
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "Detected a cycle in requirements files: `{}` -> `{}` -> `{}`",
+                a.display(),
+                b.display(),
+                a.display()
+            )
+        );
+    }
+}
@@ -0,0 +1,2090 @@
+//! Resolution of optional dependencies (extras) and dependency groups, including self-references
+//! such as `spam[test]` in the optional dependencies of `spam`.
+
+use indexmap::{IndexMap, IndexSet};
+use pep508_rs::{ExtraName, MarkerTree, PackageName, Requirement};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::{DependencyGroupSpecifier, DependencyGroups, Project, PyProjectToml};
+
+/// An error resolving the optional dependencies and dependency groups
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct ResolveError(Box<ResolveErrorKind>);
+
+impl ResolveError {
+    /// The kind of error
+    pub fn kind(&self) -> &ResolveErrorKind {
+        &self.0
+    }
+}
+
+impl From<ResolveErrorKind> for ResolveError {
+    fn from(kind: ResolveErrorKind) -> Self {
+        Self(Box::new(kind))
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ResolveErrorKind {
+    #[error("Failed to find optional dependency `{name}` included by {included_by}")]
+    OptionalDependencyNotFound {
+        name: String,
+        included_by: InstallTarget,
+        /// The self-reference that requested the extra, e.g. `spam[alpha]`, boxed to keep the
+        /// error small
+        source_requirement: Option<Box<Requirement>>,
+    },
+    #[error(
+        "Dependency group `{group}` references extra `{extra}` on the project, which is not defined"
+    )]
+    GroupReferencesMissingExtra { group: String, extra: String },
+    #[error("Failed to find dependency group `{name}` included by {included_by}")]
+    DependencyGroupNotFound {
+        name: String,
+        included_by: InstallTarget,
+    },
+    #[error("Failed to find optional dependency `{0}`")]
+    UnknownExtra(String),
+    #[error("Failed to find dependency group `{0}`")]
+    UnknownGroup(String),
+    #[error("Detected a cycle in `optional-dependencies` and `dependency-groups`: {0}")]
+    Cycle(Cycle),
+    #[error("The self-reference `{requirement}` in {target} was not expanded")]
+    UnexpandedSelfReference {
+        requirement: String,
+        target: InstallTarget,
+    },
+    #[error("`{first}` and `{second}` are the same name after PEP 685 normalization")]
+    NameCollision {
+        first: InstallTarget,
+        second: InstallTarget,
+    },
+    #[cfg(feature = "requirements-txt")]
+    #[error("Dependency group `{group}` includes the file `{path}`, which can only be read when resolving with a project root")]
+    RequirementsFileWithoutRoot { group: String, path: String },
+    #[cfg(feature = "requirements-txt")]
+    #[error("Dependency group `{group}` includes the file `{path}`, which is not a relative path inside the project root")]
+    RequirementsFileOutsideRoot { group: String, path: String },
+    #[cfg(feature = "requirements-txt")]
+    #[error("Failed to read requirements file `{}`", path.display())]
+    RequirementsFileIo {
+        path: std::path::PathBuf,
+        #[source]
+        err: std::io::Error,
+    },
+    #[cfg(feature = "requirements-txt")]
+    #[error("Invalid line {line} in requirements file `{}`: {message}", path.display())]
+    RequirementsFileSyntax {
+        path: std::path::PathBuf,
+        line: usize,
+        message: String,
+    },
+    #[cfg(feature = "requirements-txt")]
+    #[error("Detected a cycle in requirements files: {}", display_file_cycle(.0))]
+    RequirementsFileCycle(Vec<std::path::PathBuf>),
+    #[cfg(feature = "requirements-txt")]
+    #[error("Requirements file `{}` includes `{include}`, which is outside the project root", path.display())]
+    RequirementsFileIncludeOutsideRoot {
+        path: std::path::PathBuf,
+        include: String,
+    },
+}
+
+/// Display a cycle of requirements files, e.g., `a.txt` -> `b.txt` -> `a.txt`.
+#[cfg(feature = "requirements-txt")]
+fn display_file_cycle(files: &[std::path::PathBuf]) -> String {
+    let mut files: Vec<String> = files
+        .iter()
+        .map(|file| format!("`{}`", file.display()))
+        .collect();
+    if let Some(first) = files.first().cloned() {
+        files.push(first);
+    }
+    files.join(" -> ")
+}
+
+/// An extra or a dependency group.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum InstallTarget {
+    /// An entry in `project.optional-dependencies`
+    Extra(String),
+    /// An entry in `dependency-groups`
+    Group(String),
+}
+
+/// Display a target, e.g., `extra:test` or `group:dev`.
+impl Display for InstallTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstallTarget::Extra(name) => write!(f, "extra:{name}"),
+            InstallTarget::Group(name) => write!(f, "group:{name}"),
+        }
+    }
+}
+
+/// A cycle of extras and dependency groups including each other, together with the chain of
+/// includes that led into it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    /// The includes from the entry point of resolution to the last target of the cycle
+    path: Vec<InstallTarget>,
+    /// The index in `path` of the target that was reached twice
+    start: usize,
+}
+
+impl Cycle {
+    /// Create a cycle from the targets being resolved, where `start` is the one reached again.
+    fn new(parents: &[InstallTarget], start: usize) -> Self {
+        Self {
+            path: parents.to_vec(),
+            start,
+        }
+    }
+
+    /// The extras and groups in the cycle, starting with the one that was reached twice
+    pub fn targets(&self) -> &[InstallTarget] {
+        &self.path[self.start..]
+    }
+
+    /// The chain of includes from the extra or group where resolution started, which is the
+    /// first element, to the last target of the cycle.
+    pub fn path(&self) -> &[InstallTarget] {
+        &self.path
+    }
+}
+
+/// Display the chain leading into the cycle and the cycle itself, e.g.,
+/// `group:dev` -> `extra:a` -> `extra:b` -> `extra:a`.
+impl Display for Cycle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let [first, rest @ ..] = self.path.as_slice() else {
+            return Ok(());
+        };
+        write!(f, "`{first}`")?;
+        for target in rest {
+            write!(f, " -> `{target}`")?;
+        }
+        write!(f, " -> `{}`", self.path[self.start])?;
+        Ok(())
+    }
+}
+
+/// The optional dependencies and dependency groups, with self-references and includes replaced by
+/// the requirements they reference.
+///
+/// The extras and groups are keyed by their PEP 685 normalized names, e.g. `group-one` for
+/// `Group_One`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolvedDependencies {
+    /// The requirements of each extra
+    pub optional_dependencies: IndexMap<String, Vec<Requirement>>,
+    /// The requirements of each dependency group
+    pub dependency_groups: IndexMap<String, Vec<Requirement>>,
+}
+
+impl ResolvedDependencies {
+    /// The requirements of an extra, looked up by its PEP 685 normalized name, so `My_Extra`
+    /// finds `my-extra`.
+    pub fn get_extra(&self, name: &str) -> Option<&[Requirement]> {
+        self.optional_dependencies
+            .get(&normalize_name(name))
+            .map(Vec::as_slice)
+    }
+
+    /// The requirements of a dependency group, looked up by its PEP 685 normalized name, so
+    /// `My_Group` finds `my-group`.
+    pub fn get_group(&self, name: &str) -> Option<&[Requirement]> {
+        self.dependency_groups
+            .get(&normalize_name(name))
+            .map(Vec::as_slice)
+    }
+
+    /// Return, for each extra and group, the packages that are required more than once after
+    /// resolution, e.g. because two included groups both require them.
+    ///
+    /// The keys are the targets displayed as `extra:<name>` or `group:<name>`, and only targets
+    /// with duplicates are included.
+    pub fn duplicate_in_resolved(&self) -> IndexMap<String, Vec<PackageName>> {
+        let extras = self
+            .optional_dependencies
+            .iter()
+            .map(|(extra, requirements)| (InstallTarget::Extra(extra.clone()), requirements));
+        let groups = self
+            .dependency_groups
+            .iter()
+            .map(|(group, requirements)| (InstallTarget::Group(group.clone()), requirements));
+
+        let mut duplicates = IndexMap::new();
+        for (target, requirements) in extras.chain(groups) {
+            let mut seen = IndexSet::new();
+            let mut duplicated = IndexSet::new();
+            for requirement in requirements {
+                if !seen.insert(&requirement.name) {
+                    duplicated.insert(requirement.name.clone());
+                }
+            }
+            if !duplicated.is_empty() {
+                duplicates.insert(target.to_string(), duplicated.into_iter().collect());
+            }
+        }
+        duplicates
+    }
+
+    /// Within each extra and group, merge the requirements that only differ in their extras into a
+    /// single requirement requesting all of them, e.g. `pkg[a]` and `pkg[b]` into `pkg[a,b]`.
+    ///
+    /// Requirements on the same package with different version specifiers, URLs or markers are
+    /// kept separate. The merged requirement takes the position of the first one.
+    pub fn merge_extras(&mut self) {
+        for requirements in self
+            .optional_dependencies
+            .values_mut()
+            .chain(self.dependency_groups.values_mut())
+        {
+            let mut merged: Vec<Requirement> = Vec::with_capacity(requirements.len());
+            for requirement in requirements.drain(..) {
+                let existing = merged.iter_mut().find(|existing| {
+                    existing.name == requirement.name
+                        && existing.version_or_url == requirement.version_or_url
+                        && existing.marker == requirement.marker
+                });
+                match existing {
+                    Some(existing) => {
+                        for extra in requirement.extras {
+                            if !existing.extras.contains(&extra) {
+                                existing.extras.push(extra);
+                            }
+                        }
+                    }
+                    None => merged.push(requirement),
+                }
+            }
+            *requirements = merged;
+        }
+    }
+
+    /// Render the resolved optional dependencies as core metadata `Requires-Dist` lines.
+    ///
+    /// The requirements of each extra get an `extra == "<name>"` marker ANDed to their own
+    /// marker, with the parentheses a marker containing `or` needs. Extras with names that are
+    /// not valid PEP 685 names can't be expressed in a marker and are skipped.
+    pub fn requires_dist_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (extra, requirements) in &self.optional_dependencies {
+            let Ok(extra) = ExtraName::from_str(extra) else {
+                continue;
+            };
+            let Ok(extra_marker) = MarkerTree::from_str(&format!("extra == '{extra}'")) else {
+                continue;
+            };
+            for requirement in requirements {
+                let mut requirement = requirement.clone();
+                requirement.marker.and(extra_marker.clone());
+                lines.push(format!("Requires-Dist: {requirement}"));
+            }
+        }
+        lines
+    }
+
+    /// Check that no extra or group still contains a self-reference with extras, such as
+    /// `spam[test]` for the project `spam`, which resolution should have expanded.
+    ///
+    /// Self-references without extras are kept by resolution and are allowed.
+    pub fn assert_fully_resolved(&self, project_name: &str) -> Result<(), ResolveError> {
+        let Ok(project_name) = PackageName::from_str(project_name) else {
+            // Without a valid project name, nothing can be a self-reference
+            return Ok(());
+        };
+        let extras = self
+            .optional_dependencies
+            .iter()
+            .map(|(extra, requirements)| (InstallTarget::Extra(extra.clone()), requirements));
+        let groups = self
+            .dependency_groups
+            .iter()
+            .map(|(group, requirements)| (InstallTarget::Group(group.clone()), requirements));
+        for (target, requirements) in extras.chain(groups) {
+            for requirement in requirements {
+                if matches!(self_reference(requirement, &project_name), Some(extras) if !extras.is_empty())
+                {
+                    return Err(ResolveErrorKind::UnexpandedSelfReference {
+                        requirement: requirement.to_string(),
+                        target,
+                    }
+                    .into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Convert the resolved dependency groups back into a `[dependency-groups]` table, with all
+    /// includes flattened into plain requirements.
+    ///
+    /// The optional dependencies are not part of the result.
+    pub fn to_dependency_groups(&self) -> DependencyGroups {
+        DependencyGroups(
+            self.dependency_groups
+                .iter()
+                .map(|(group, requirements)| {
+                    let specifiers = requirements
+                        .iter()
+                        .cloned()
+                        .map(DependencyGroupSpecifier::String)
+                        .collect();
+                    (group.clone(), specifiers)
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Like [`ResolvedDependencies`], but with the extras and groups sorted by name instead of in
+/// declaration order, as returned by [`PyProjectToml::resolve_sorted_output`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SortedResolvedDependencies {
+    /// The requirements of each extra
+    pub optional_dependencies: BTreeMap<String, Vec<Requirement>>,
+    /// The requirements of each dependency group
+    pub dependency_groups: BTreeMap<String, Vec<Requirement>>,
+}
+
+/// The result of [`PyProjectToml::resolve_full`]: the flattened requirements together with the
+/// include graph they were flattened from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolvedFull {
+    /// The flattened requirements, as returned by [`PyProjectToml::resolve`]
+    pub dependencies: ResolvedDependencies,
+    /// For each extra and dependency group, the extras and groups it directly includes, either
+    /// through a self-reference such as `spam[test]` or through `include-group`, in declaration
+    /// order.
+    pub includes: IndexMap<InstallTarget, Vec<InstallTarget>>,
+}
+
+/// Normalize an extra or group name as specified in PEP 685.
+///
+/// Names that are not valid identifiers are returned unchanged.
+pub(crate) fn normalize_name(name: &str) -> String {
+    ExtraName::from_str(name)
+        .map(|name| name.to_string())
+        .unwrap_or_else(|_| name.to_string())
+}
+
+/// The name an extra or group is looked up by, normalized unless `normalize` is off.
+fn key(name: &str, normalize: bool) -> String {
+    if normalize {
+        normalize_name(name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// If the requirement references the project itself, return the extras it requests.
+pub(crate) fn self_reference<'a>(
+    requirement: &'a Requirement,
+    project_name: &PackageName,
+) -> Option<&'a [ExtraName]> {
+    if requirement.name == *project_name {
+        Some(&requirement.extras)
+    } else {
+        None
+    }
+}
+
+/// Options for [`PyProjectToml::resolve_with_options`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolveOptions {
+    /// Compare extra and group names after PEP 685 normalization, so `group_one` and `group-one`
+    /// are the same group. Without it, names must match exactly as written. Defaults to `true`.
+    pub normalize: bool,
+}
+
+impl Default for ResolveOptions {
+    fn default() -> Self {
+        Self { normalize: true }
+    }
+}
+
+/// Resolves extras and dependency groups, memoizing the results.
+struct Resolver<'a> {
+    project_name: Option<PackageName>,
+    /// Whether names are compared after normalization
+    normalize: bool,
+    /// The extras, by normalized name
+    extras: IndexMap<String, (&'a str, &'a [Requirement])>,
+    /// The dependency groups, by normalized name
+    groups: IndexMap<String, (&'a str, &'a [DependencyGroupSpecifier])>,
+    /// The resolved requirements, borrowed from the `pyproject.toml` unless they are expanded
+    /// self-references or read from a requirements file
+    resolved: IndexMap<InstallTarget, Vec<Cow<'a, Requirement>>>,
+    includes: IndexMap<InstallTarget, Vec<InstallTarget>>,
+    /// The targets currently being resolved, to detect cycles
+    parents: Vec<InstallTarget>,
+    /// The directory that requirements files are relative to
+    #[cfg(feature = "requirements-txt")]
+    root: Option<&'a std::path::Path>,
+}
+
+impl<'a> Resolver<'a> {
+    fn new(
+        pyproject_toml: &'a PyProjectToml,
+        options: ResolveOptions,
+    ) -> Result<Self, ResolveError> {
+        // Helper function to index extras or groups by normalized name, rejecting collisions
+        fn by_normalized_name<'a, T>(
+            entries: impl Iterator<Item = (&'a String, &'a Vec<T>)>,
+            target: fn(String) -> InstallTarget,
+            normalize: bool,
+        ) -> Result<IndexMap<String, (&'a str, &'a [T])>, ResolveError> {
+            let mut by_name = IndexMap::new();
+            for (name, values) in entries {
+                if let Some((first, _)) =
+                    by_name.insert(key(name, normalize), (name.as_str(), values.as_slice()))
+                {
+                    return Err(ResolveErrorKind::NameCollision {
+                        first: target(first.to_string()),
+                        second: target(name.clone()),
+                    }
+                    .into());
+                }
+            }
+            Ok(by_name)
+        }
+
+        let project = pyproject_toml.project.as_ref();
+        let extras = by_normalized_name(
+            project
+                .and_then(|project| project.optional_dependencies.as_ref())
+                .into_iter()
+                .flat_map(|extras| extras.iter()),
+            InstallTarget::Extra,
+            options.normalize,
+        )?;
+        let groups = by_normalized_name(
+            pyproject_toml
+                .dependency_groups
+                .iter()
+                .flat_map(|groups| groups.iter()),
+            InstallTarget::Group,
+            options.normalize,
+        )?;
+        Ok(Self {
+            project_name: project.and_then(|project| project.normalized_name().ok()),
+            normalize: options.normalize,
+            extras,
+            groups,
+            resolved: IndexMap::new(),
+            includes: IndexMap::new(),
+            parents: Vec::new(),
+            #[cfg(feature = "requirements-txt")]
+            root: None,
+        })
+    }
+
+    /// Read the requirements file `path` that `group` includes, relative to the project root.
+    #[cfg(feature = "requirements-txt")]
+    fn requirements_file(&self, group: &str, path: &str) -> Result<Vec<Requirement>, ResolveError> {
+        // Only files inside the project root can be included, no absolute paths or `..`
+        let inside_root = std::path::Path::new(path).components().all(|component| {
+            matches!(
+                component,
+                std::path::Component::Normal(_) | std::path::Component::CurDir
+            )
+        });
+        if !inside_root {
+            return Err(ResolveErrorKind::RequirementsFileOutsideRoot {
+                group: group.to_string(),
+                path: path.to_string(),
+            }
+            .into());
+        }
+        match self.root {
+            Some(root) => crate::requirements_txt::read_requirements(root, group, path),
+            None => Err(ResolveErrorKind::RequirementsFileWithoutRoot {
+                group: group.to_string(),
+                path: path.to_string(),
+            }
+            .into()),
+        }
+    }
+
+    /// Look up an extra referenced by `included_by`, through `requirement` if it's a
+    /// self-reference.
+    fn extra(
+        &self,
+        name: &str,
+        included_by: &InstallTarget,
+        requirement: Option<&Requirement>,
+    ) -> Result<InstallTarget, ResolveError> {
+        match self.extras.get(&key(name, self.normalize)) {
+            Some((extra, _)) => Ok(InstallTarget::Extra(extra.to_string())),
+            None => match included_by {
+                InstallTarget::Group(group) => Err(ResolveErrorKind::GroupReferencesMissingExtra {
+                    group: group.clone(),
+                    extra: name.to_string(),
+                }
+                .into()),
+                InstallTarget::Extra(_) => Err(ResolveErrorKind::OptionalDependencyNotFound {
+                    name: name.to_string(),
+                    included_by: included_by.clone(),
+                    source_requirement: requirement.cloned().map(Box::new),
+                }
+                .into()),
+            },
+        }
+    }
+
+    /// Look up a dependency group referenced by `included_by`.
+    fn group(
+        &self,
+        name: &str,
+        included_by: &InstallTarget,
+    ) -> Result<InstallTarget, ResolveError> {
+        match self.groups.get(&key(name, self.normalize)) {
+            Some((group, _)) => Ok(InstallTarget::Group(group.to_string())),
+            None => Err(ResolveErrorKind::DependencyGroupNotFound {
+                name: name.to_string(),
+                included_by: included_by.clone(),
+            }
+            .into()),
+        }
+    }
+
+    /// Resolve an extra or group that is known to exist.
+    fn resolve(&mut self, target: &InstallTarget) -> Result<(), ResolveError> {
+        // If there is a cycle, return an error
+        if let Some(pos) = self.parents.iter().position(|parent| parent == target) {
+            return Err(ResolveErrorKind::Cycle(Cycle::new(&self.parents, pos)).into());
+        }
+        // If the target has already been resolved, exit early
+        if self.resolved.contains_key(target) {
+            return Ok(());
+        }
+
+        self.parents.push(target.clone());
+        let mut requirements = Vec::new();
+        let mut includes = Vec::new();
+        match target {
+            InstallTarget::Extra(extra) => {
+                let (_, extra_requirements) = self.extras[&key(extra, self.normalize)];
+                for requirement in extra_requirements {
+                    self.resolve_requirement(
+                        Cow::Borrowed(requirement),
+                        target,
+                        &mut requirements,
+                        &mut includes,
+                    )?;
+                }
+            }
+            InstallTarget::Group(group) => {
+                let (_, specifiers) = self.groups[&key(group, self.normalize)];
+                for specifier in specifiers {
+                    match specifier {
+                        DependencyGroupSpecifier::String(requirement) => {
+                            self.resolve_requirement(
+                                Cow::Borrowed(requirement),
+                                target,
+                                &mut requirements,
+                                &mut includes,
+                            )?;
+                        }
+                        DependencyGroupSpecifier::Table { include_group } => {
+                            let included = self.group(include_group, target)?;
+                            self.resolve(&included)?;
+                            requirements.extend(self.resolved[&included].iter().cloned());
+                            includes.push(included);
+                        }
+                        #[cfg(feature = "include-extra")]
+                        DependencyGroupSpecifier::IncludeExtra { include_extra } => {
+                            let included = self.extra(include_extra, target, None)?;
+                            self.resolve(&included)?;
+                            requirements.extend(self.resolved[&included].iter().cloned());
+                            includes.push(included);
+                        }
+                        #[cfg(feature = "requirements-txt")]
+                        DependencyGroupSpecifier::Path { path } => {
+                            for requirement in self.requirements_file(group, path)? {
+                                self.resolve_requirement(
+                                    Cow::Owned(requirement),
+                                    target,
+                                    &mut requirements,
+                                    &mut includes,
+                                )?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        self.parents.pop();
+
+        self.resolved.insert(target.clone(), requirements);
+        self.includes.insert(target.clone(), includes);
+        Ok(())
+    }
+
+    /// Add a requirement of `target`, expanding it if it's a self-reference with extras.
+    ///
+    /// Only the requirements that a self-reference expands to are cloned, since their marker
+    /// changes.
+    fn resolve_requirement(
+        &mut self,
+        requirement: Cow<'a, Requirement>,
+        target: &InstallTarget,
+        requirements: &mut Vec<Cow<'a, Requirement>>,
+        includes: &mut Vec<InstallTarget>,
+    ) -> Result<(), ResolveError> {
+        let extras = match &self.project_name {
+            Some(project_name) => self_reference(&requirement, project_name),
+            None => None,
+        };
+        match extras {
+            Some(extras) if !extras.is_empty() => {
+                for extra in extras {
+                    let included = self.extra(extra.as_ref(), target, Some(&requirement))?;
+                    self.resolve(&included)?;
+                    // The included requirements only apply where the self-reference applies
+                    requirements.extend(self.resolved[&included].iter().map(|included| {
+                        let mut included = included.as_ref().clone();
+                        included.marker.and(requirement.marker.clone());
+                        Cow::Owned(included)
+                    }));
+                    includes.push(included);
+                }
+            }
+            _ => requirements.push(requirement),
+        }
+        Ok(())
+    }
+
+    /// Resolve all extras and groups, in declaration order.
+    fn resolve_all(mut self) -> Result<ResolvedFull, ResolveError> {
+        // The targets with their normalized names
+        let targets: Vec<(String, InstallTarget)> =
+            self.extras
+                .iter()
+                .map(|(name, (extra, _))| (name.clone(), InstallTarget::Extra(extra.to_string())))
+                .chain(self.groups.iter().map(|(name, (group, _))| {
+                    (name.clone(), InstallTarget::Group(group.to_string()))
+                }))
+                .collect();
+        // Groups can include extras but not the other way around, so resolving the groups first
+        // reports a cycle together with the group it was reached from
+        let (groups, extras): (Vec<&InstallTarget>, Vec<&InstallTarget>) = targets
+            .iter()
+            .map(|(_, target)| target)
+            .partition(|target| matches!(target, InstallTarget::Group(_)));
+        for target in groups.into_iter().chain(extras) {
+            self.resolve(target)?;
+        }
+
+        let mut full = ResolvedFull::default();
+        for (name, target) in targets {
+            let requirements: Vec<Requirement> = self
+                .resolved
+                .swap_remove(&target)
+                .into_iter()
+                .flatten()
+                .map(Cow::into_owned)
+                .collect();
+            let includes = self.includes.swap_remove(&target).unwrap_or_default();
+            match &target {
+                InstallTarget::Extra(_) => {
+                    full.dependencies
+                        .optional_dependencies
+                        .insert(name, requirements);
+                }
+                InstallTarget::Group(_) => {
+                    full.dependencies
+                        .dependency_groups
+                        .insert(name, requirements);
+                }
+            }
+            full.includes.insert(target, includes);
+        }
+        Ok(full)
+    }
+}
+
+impl Project {
+    /// Return the base dependencies followed by the requirements of all resolved extras, e.g. to
+    /// install the project with every extra.
+    ///
+    /// Identical requirements collapse into the first occurrence, also across extras, while
+    /// requirements on the same package that differ in any way are kept. Dependency groups are
+    /// not included, since they are never installed with the project.
+    pub fn all_dependencies(&self, resolved: &ResolvedDependencies) -> Vec<Requirement> {
+        let mut all: Vec<Requirement> = Vec::new();
+        let requirements = self
+            .dependencies
+            .iter()
+            .flatten()
+            .chain(resolved.optional_dependencies.values().flatten());
+        for requirement in requirements {
+            if !all.contains(requirement) {
+                all.push(requirement.clone());
+            }
+        }
+        all
+    }
+}
+
+impl PyProjectToml {
+    /// Resolve the optional dependencies (extras) and dependency groups into flat lists of
+    /// requirements.
+    ///
+    /// Self-references to extras of the project, such as `spam[test]` in the project `spam`, are
+    /// replaced by the requirements of those extras, and `include-group` entries by the
+    /// requirements of the included group. With the `include-extra` feature, `include-extra`
+    /// entries are replaced by the requirements of the extra like a self-reference. The marker of
+    /// a self-reference is added to the requirements it expands to, so
+    /// `spam[test]; python_version >= "3.10"` only pulls in the requirements of `test` on Python
+    /// 3.10 or later. A self-reference without extras is kept as is. Names are compared after
+    /// normalization. Returns an error if an extra or group references one that doesn't exist, or
+    /// if there is a cycle.
+    ///
+    /// The function makes no guarantee about whether duplicates are removed, use
+    /// [`PyProjectToml::resolve_dedup`] for that.
+    pub fn resolve(&self) -> Result<ResolvedDependencies, ResolveError> {
+        Ok(self.resolve_full()?.dependencies)
+    }
+
+    /// Like [`PyProjectToml::resolve`], but remove requirements that are identical to an earlier
+    /// one in the same extra or group, e.g. when two included groups both require `requests`.
+    ///
+    /// Requirements on the same package that differ in their extras, version specifiers, URL or
+    /// markers are kept. The first occurrence keeps its position.
+    pub fn resolve_dedup(&self) -> Result<ResolvedDependencies, ResolveError> {
+        let mut resolved = self.resolve()?;
+        for requirements in resolved
+            .optional_dependencies
+            .values_mut()
+            .chain(resolved.dependency_groups.values_mut())
+        {
+            let mut unique: Vec<Requirement> = Vec::with_capacity(requirements.len());
+            for requirement in requirements.drain(..) {
+                if !unique.contains(&requirement) {
+                    unique.push(requirement);
+                }
+            }
+            *requirements = unique;
+        }
+        Ok(resolved)
+    }
+
+    /// Like [`PyProjectToml::resolve`], but with the extras and groups sorted by name, e.g. for
+    /// serializing to a format that requires sorted keys.
+    pub fn resolve_sorted_output(&self) -> Result<SortedResolvedDependencies, ResolveError> {
+        let resolved = self.resolve()?;
+        Ok(SortedResolvedDependencies {
+            optional_dependencies: resolved.optional_dependencies.into_iter().collect(),
+            dependency_groups: resolved.dependency_groups.into_iter().collect(),
+        })
+    }
+
+    /// Return the extras and groups that are declared as an empty list.
+    ///
+    /// See [`PyProjectToml::resolves_empty_targets`] for extras and groups that are only empty
+    /// after resolution, e.g. a group that only includes empty groups.
+    pub fn empty_targets(&self) -> Vec<InstallTarget> {
+        let extras = self
+            .project
+            .iter()
+            .filter_map(|project| project.optional_dependencies.as_ref())
+            .flat_map(|extras| extras.iter())
+            .filter(|(_, requirements)| requirements.is_empty())
+            .map(|(extra, _)| InstallTarget::Extra(extra.clone()));
+        let groups = self
+            .dependency_groups
+            .iter()
+            .flat_map(|groups| groups.iter())
+            .filter(|(_, specifiers)| specifiers.is_empty())
+            .map(|(group, _)| InstallTarget::Group(group.clone()));
+        extras.chain(groups).collect()
+    }
+
+    /// Return the extras and groups that have no requirements after resolution.
+    pub fn resolves_empty_targets(&self) -> Result<Vec<InstallTarget>, ResolveError> {
+        let resolved = self.resolve()?;
+        let extras = resolved
+            .optional_dependencies
+            .into_iter()
+            .filter(|(_, requirements)| requirements.is_empty())
+            .map(|(extra, _)| InstallTarget::Extra(extra));
+        let groups = resolved
+            .dependency_groups
+            .into_iter()
+            .filter(|(_, requirements)| requirements.is_empty())
+            .map(|(group, _)| InstallTarget::Group(group));
+        Ok(extras.chain(groups).collect())
+    }
+
+    /// Like [`PyProjectToml::resolve`], but also return which extras and groups each extra and
+    /// group directly includes, computed in the same pass.
+    pub fn resolve_full(&self) -> Result<ResolvedFull, ResolveError> {
+        Resolver::new(self, ResolveOptions::default())?.resolve_all()
+    }
+
+    /// Like [`PyProjectToml::resolve`], but with options, e.g. to compare extra and group names
+    /// exactly as written instead of after normalization, as older tools did.
+    ///
+    /// Without normalization, the resolved extras and groups are keyed by their names as
+    /// written. The extras of a self-reference such as `spam[Test]` are always normalized when
+    /// the requirement is parsed, so they only match extras declared with normalized names.
+    pub fn resolve_with_options(
+        &self,
+        options: ResolveOptions,
+    ) -> Result<ResolvedDependencies, ResolveError> {
+        Ok(Resolver::new(self, options)?.resolve_all()?.dependencies)
+    }
+
+    /// Like [`PyProjectToml::resolve`], but also read the requirements files that dependency
+    /// groups include with `{ path = "..." }`, relative to `root`, the directory containing the
+    /// `pyproject.toml`.
+    ///
+    /// Requirements files can include other requirements files with `-r`; a cycle of such
+    /// includes is an error.
+    #[cfg(feature = "requirements-txt")]
+    pub fn resolve_with_root(
+        &self,
+        root: &std::path::Path,
+    ) -> Result<ResolvedDependencies, ResolveError> {
+        let mut resolver = Resolver::new(self, ResolveOptions::default())?;
+        resolver.root = Some(root);
+        Ok(resolver.resolve_all()?.dependencies)
+    }
+}
+
+/// For each extra, the extras it transitively includes through self-references, and the cycles
+/// found on the way.
+fn self_closure(
+    extras: &IndexMap<String, Vec<Requirement>>,
+    project_name: &str,
+) -> (IndexMap<String, Vec<String>>, Vec<Cycle>) {
+    // Helper function to collect the extras reachable from a list of requirements
+    fn visit(
+        extras: &IndexMap<String, &Vec<Requirement>>,
+        project_name: &PackageName,
+        requirements: &[Requirement],
+        reached: &mut IndexSet<String>,
+        parents: &mut Vec<String>,
+        cycles: &mut Vec<Cycle>,
+    ) {
+        for requirement in requirements {
+            for extra in self_reference(requirement, project_name)
+                .into_iter()
+                .flatten()
+            {
+                let extra = extra.to_string();
+                if let Some(pos) = parents.iter().position(|parent| *parent == extra) {
+                    let path: Vec<InstallTarget> =
+                        parents.iter().cloned().map(InstallTarget::Extra).collect();
+                    let cycle = Cycle::new(&path, pos);
+                    // A cycle is found again from each of its extras
+                    if !cycles
+                        .iter()
+                        .any(|known| is_rotation(known.targets(), cycle.targets()))
+                    {
+                        cycles.push(cycle);
+                    }
+                    continue;
+                }
+                if !reached.insert(extra.clone()) {
+                    continue;
+                }
+                if let Some(requirements) = extras.get(&extra) {
+                    parents.push(extra);
+                    visit(extras, project_name, requirements, reached, parents, cycles);
+                    parents.pop();
+                }
+            }
+        }
+    }
+
+    // Whether `a` and `b` are the same cycle, starting at different targets
+    fn is_rotation(a: &[InstallTarget], b: &[InstallTarget]) -> bool {
+        a.len() == b.len() && (0..a.len()).any(|i| a[i..].iter().chain(&a[..i]).eq(b))
+    }
+
+    let Ok(project_name) = PackageName::from_str(project_name) else {
+        // Without a valid project name, nothing can be a self-reference
+        let closure = extras
+            .keys()
+            .map(|extra| (extra.clone(), Vec::new()))
+            .collect();
+        return (closure, Vec::new());
+    };
+    let normalized: IndexMap<String, &Vec<Requirement>> = extras
+        .iter()
+        .map(|(extra, requirements)| (normalize_name(extra), requirements))
+        .collect();
+
+    let mut closure = IndexMap::new();
+    let mut cycles = Vec::new();
+    for (extra, requirements) in extras {
+        // Seed with the extra itself so that it isn't revisited through a cycle
+        let mut reached = IndexSet::from([normalize_name(extra)]);
+        let mut parents = vec![normalize_name(extra)];
+        visit(
+            &normalized,
+            &project_name,
+            requirements,
+            &mut reached,
+            &mut parents,
+            &mut cycles,
+        );
+        closure.insert(extra.clone(), reached.into_iter().skip(1).collect());
+    }
+    (closure, cycles)
+}
+
+/// Queries on the extra-to-extra graph of [`Project::optional_dependencies`], following
+/// self-references such as `spam[test]` in the project `spam`.
+pub trait OptionalDependencies {
+    /// For each extra, return the names of the other extras it transitively includes through
+    /// self-references, ignoring all third-party requirements.
+    ///
+    /// The included extras are returned normalized, in the order they are first reached. Extras
+    /// that are not defined are listed but not followed.
+    ///
+    /// Cycles don't fail the closure: each extra is visited at most once, so an extra in a cycle
+    /// lists the others but never itself. Use [`OptionalDependencies::self_closure_cycles`] to
+    /// report them.
+    fn resolve_self_closure(&self, project_name: &str) -> IndexMap<String, Vec<String>>;
+
+    /// Return the cycles between extras that [`OptionalDependencies::resolve_self_closure`]
+    /// stops at, each reported once, starting from the extra where it was first found.
+    fn self_closure_cycles(&self, project_name: &str) -> Vec<Cycle>;
+
+    /// Return the extras that installing `extra` also enables through self-references, e.g. that
+    /// `spam[all]` enables `a`, `b` and `c`.
+    ///
+    /// The extras are returned as declared, in the order they are first reached, without `extra`
+    /// itself. Returns an error if `extra` or an extra it references is not defined, or if there
+    /// is a cycle.
+    fn transitive_extras(
+        &self,
+        extra: &str,
+        project_name: &str,
+    ) -> Result<Vec<String>, ResolveError>;
+}
+
+impl OptionalDependencies for IndexMap<String, Vec<Requirement>> {
+    fn resolve_self_closure(&self, project_name: &str) -> IndexMap<String, Vec<String>> {
+        self_closure(self, project_name).0
+    }
+
+    fn self_closure_cycles(&self, project_name: &str) -> Vec<Cycle> {
+        self_closure(self, project_name).1
+    }
+
+    fn transitive_extras(
+        &self,
+        extra: &str,
+        project_name: &str,
+    ) -> Result<Vec<String>, ResolveError> {
+        // Helper function to follow the self-references of an extra, depth first
+        fn visit<'a>(
+            extras: &IndexMap<String, (&'a str, &'a [Requirement])>,
+            project_name: &PackageName,
+            current: &'a str,
+            parents: &mut Vec<InstallTarget>,
+            reached: &mut IndexSet<&'a str>,
+        ) -> Result<(), ResolveError> {
+            let target = InstallTarget::Extra(current.to_string());
+            if let Some(pos) = parents.iter().position(|parent| *parent == target) {
+                return Err(ResolveErrorKind::Cycle(Cycle::new(parents, pos)).into());
+            }
+            if !reached.insert(current) {
+                return Ok(());
+            }
+            let (_, requirements) = extras[&normalize_name(current)];
+            parents.push(target);
+            for requirement in requirements {
+                for extra in self_reference(requirement, project_name)
+                    .into_iter()
+                    .flatten()
+                {
+                    let Some((included, _)) = extras.get(&normalize_name(extra.as_ref())) else {
+                        return Err(ResolveErrorKind::OptionalDependencyNotFound {
+                            name: extra.to_string(),
+                            included_by: parents[parents.len() - 1].clone(),
+                            source_requirement: Some(Box::new(requirement.clone())),
+                        }
+                        .into());
+                    };
+                    visit(extras, project_name, included, parents, reached)?;
+                }
+            }
+            parents.pop();
+            Ok(())
+        }
+
+        let extras: IndexMap<String, (&str, &[Requirement])> = self
+            .iter()
+            .map(|(extra, requirements)| {
+                (
+                    normalize_name(extra),
+                    (extra.as_str(), requirements.as_slice()),
+                )
+            })
+            .collect();
+        let Some((root, _)) = extras.get(&normalize_name(extra)) else {
+            return Err(ResolveErrorKind::UnknownExtra(extra.to_string()).into());
+        };
+        let Ok(project_name) = PackageName::from_str(project_name) else {
+            // Without a valid project name, nothing can be a self-reference
+            return Ok(Vec::new());
+        };
+        let mut reached = IndexSet::new();
+        visit(&extras, &project_name, root, &mut Vec::new(), &mut reached)?;
+        Ok(reached
+            .into_iter()
+            .skip(1)
+            .map(ToString::to_string)
+            .collect())
+    }
+}
+
+impl DependencyGroups {
+    /// Return the groups that `group` directly includes with `include-group`, as written, without
+    /// checking that they exist.
+    ///
+    /// Returns an empty list if `group` is not defined.
+    pub fn includes(&self, group: &str) -> Vec<&str> {
+        let group = normalize_name(group);
+        self.iter()
+            .filter(|(name, _)| normalize_name(name) == group)
+            .flat_map(|(_, specifiers)| specifiers)
+            .filter_map(|specifier| match specifier {
+                DependencyGroupSpecifier::Table { include_group } => Some(include_group.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Return all groups that `group` includes, directly or through other groups, as declared,
+    /// in the order they are first reached.
+    ///
+    /// Cycles are not an error here: each group is visited once and `group` itself is not listed.
+    /// Returns an error if `group` or an included group is not defined.
+    pub fn includes_transitive(&self, group: &str) -> Result<Vec<&str>, ResolveError> {
+        // Helper function to follow the includes of a group, depth first
+        fn visit<'a>(
+            dependency_groups: &'a DependencyGroups,
+            groups: &IndexMap<String, &'a str>,
+            current: &'a str,
+            reached: &mut IndexSet<&'a str>,
+        ) -> Result<(), ResolveError> {
+            for include in dependency_groups.includes(current) {
+                let Some(&included) = groups.get(&normalize_name(include)) else {
+                    return Err(ResolveErrorKind::DependencyGroupNotFound {
+                        name: include.to_string(),
+                        included_by: InstallTarget::Group(current.to_string()),
+                    }
+                    .into());
+                };
+                if reached.insert(included) {
+                    visit(dependency_groups, groups, included, reached)?;
+                }
+            }
+            Ok(())
+        }
+
+        let groups: IndexMap<String, &str> = self
+            .keys()
+            .map(|name| (normalize_name(name), name.as_str()))
+            .collect();
+        let Some(&root) = groups.get(&normalize_name(group)) else {
+            return Err(ResolveErrorKind::UnknownGroup(group.to_string()).into());
+        };
+        let mut reached = IndexSet::from([root]);
+        visit(self, &groups, root, &mut reached)?;
+        Ok(reached.into_iter().skip(1).collect())
+    }
+
+    /// Return the groups that only alias extras of the project itself.
+    ///
+    /// A group qualifies if it has at least one requirement and every requirement is a
+    /// self-reference with extras, such as `spam[test]` for the project `spam`. Includes of other
+    /// groups are allowed. Such groups could be replaced by the extras they reference.
+    pub fn pure_self_reference_groups(&self, project_name: &str) -> Vec<String> {
+        let Ok(project_name) = PackageName::from_str(project_name) else {
+            return Vec::new();
+        };
+        self.iter()
+            .filter(|(_, specifiers)| {
+                let mut requirements = specifiers
+                    .iter()
+                    .filter_map(|specifier| match specifier {
+                        DependencyGroupSpecifier::String(requirement) => Some(requirement),
+                        DependencyGroupSpecifier::Table { .. } => None,
+                        #[cfg(feature = "requirements-txt")]
+                        DependencyGroupSpecifier::Path { .. } => None,
+                        #[cfg(feature = "include-extra")]
+                        DependencyGroupSpecifier::IncludeExtra { .. } => None,
+                    })
+                    .peekable();
+                requirements.peek().is_some()
+                    && requirements.all(|requirement| {
+                        matches!(self_reference(requirement, &project_name), Some(extras) if !extras.is_empty())
+                    })
+            })
+            .map(|(group, _)| group.clone())
+            .collect()
+    }
+
+    /// Resolve the `include-group` entries like [`DependencyGroups::resolve`], but break cycles
+    /// instead of failing, e.g. to show partial results together with a warning in an editor.
+    ///
+    /// An include that would re-enter a group that is currently being resolved is skipped and the
+    /// cycle is recorded. The group where the cycle is broken lacks the requirements of the
+    /// skipped include, and which group that is depends on the declaration order. Includes of
+    /// groups that don't exist, requirements files and `include-extra` entries are skipped.
+    ///
+    /// With a `project_name`, a self-reference such as `spam[test, lint]` in the project `spam`
+    /// whose extras all name groups is expanded like includes of those groups, following the
+    /// self-referencing groups of tools that predate PEP 735. The marker of the self-reference is
+    /// added to the requirements it expands to. Other self-references are kept as they are, since
+    /// the extras of the project are not part of the groups table.
+    pub fn resolve_breaking_cycles(
+        &self,
+        project_name: Option<&str>,
+    ) -> (IndexMap<String, Vec<Requirement>>, Vec<Cycle>) {
+        // Helper function to resolve a single group, depth first
+        fn visit<'a>(
+            groups: &IndexMap<String, (&'a str, &'a [DependencyGroupSpecifier])>,
+            project_name: Option<&PackageName>,
+            group: &'a str,
+            specifiers: &'a [DependencyGroupSpecifier],
+            resolved: &mut IndexMap<&'a str, Vec<Requirement>>,
+            parents: &mut Vec<&'a str>,
+            cycles: &mut Vec<Cycle>,
+        ) {
+            parents.push(group);
+            let mut requirements = Vec::new();
+            for specifier in specifiers {
+                match specifier {
+                    DependencyGroupSpecifier::String(requirement) => {
+                        let included: Option<Vec<_>> = project_name
+                            .and_then(|project_name| self_reference(requirement, project_name))
+                            .filter(|extras| !extras.is_empty())
+                            .and_then(|extras| {
+                                extras
+                                    .iter()
+                                    .map(|extra| groups.get(&normalize_name(extra.as_ref())))
+                                    .collect()
+                            });
+                        let Some(included) = included else {
+                            requirements.push(requirement.clone());
+                            continue;
+                        };
+                        for &(included, included_specifiers) in included {
+                            if !include(
+                                groups,
+                                project_name,
+                                included,
+                                included_specifiers,
+                                resolved,
+                                parents,
+                                cycles,
+                            ) {
+                                continue;
+                            }
+                            // The included requirements only apply where the self-reference applies
+                            requirements.extend(resolved[included].iter().map(|included| {
+                                let mut included = included.clone();
+                                included.marker.and(requirement.marker.clone());
+                                included
+                            }));
+                        }
+                    }
+                    DependencyGroupSpecifier::Table { include_group } => {
+                        let Some(&(included, included_specifiers)) =
+                            groups.get(&normalize_name(include_group))
+                        else {
+                            continue;
+                        };
+                        if include(
+                            groups,
+                            project_name,
+                            included,
+                            included_specifiers,
+                            resolved,
+                            parents,
+                            cycles,
+                        ) {
+                            requirements.extend(resolved[included].iter().cloned());
+                        }
+                    }
+                    #[cfg(feature = "requirements-txt")]
+                    DependencyGroupSpecifier::Path { .. } => {}
+                    #[cfg(feature = "include-extra")]
+                    DependencyGroupSpecifier::IncludeExtra { .. } => {}
+                }
+            }
+            parents.pop();
+            resolved.insert(group, requirements);
+        }
+
+        // Helper function to resolve an included group, returns `false` if it closes a cycle
+        fn include<'a>(
+            groups: &IndexMap<String, (&'a str, &'a [DependencyGroupSpecifier])>,
+            project_name: Option<&PackageName>,
+            included: &'a str,
+            included_specifiers: &'a [DependencyGroupSpecifier],
+            resolved: &mut IndexMap<&'a str, Vec<Requirement>>,
+            parents: &mut Vec<&'a str>,
+            cycles: &mut Vec<Cycle>,
+        ) -> bool {
+            if let Some(pos) = parents.iter().position(|parent| *parent == included) {
+                let path: Vec<InstallTarget> = parents
+                    .iter()
+                    .map(|parent| InstallTarget::Group(parent.to_string()))
+                    .collect();
+                cycles.push(Cycle::new(&path, pos));
+                return false;
+            }
+            if !resolved.contains_key(included) {
+                visit(
+                    groups,
+                    project_name,
+                    included,
+                    included_specifiers,
+                    resolved,
+                    parents,
+                    cycles,
+                );
+            }
+            true
+        }
+
+        // A project name that isn't valid can't be referenced
+        let project_name = project_name.and_then(|name| PackageName::from_str(name).ok());
+        let groups: IndexMap<String, (&str, &[DependencyGroupSpecifier])> = self
+            .iter()
+            .map(|(group, specifiers)| {
+                (
+                    normalize_name(group),
+                    (group.as_str(), specifiers.as_slice()),
+                )
+            })
+            .collect();
+        let mut resolved = IndexMap::new();
+        let mut cycles = Vec::new();
+        for &(group, specifiers) in groups.values() {
+            if !resolved.contains_key(group) {
+                visit(
+                    &groups,
+                    project_name.as_ref(),
+                    group,
+                    specifiers,
+                    &mut resolved,
+                    &mut Vec::new(),
+                    &mut cycles,
+                );
+            }
+        }
+        // Return the groups in declaration order
+        let resolved = self
+            .keys()
+            .map(|group| {
+                let requirements = resolved.swap_remove(group.as_str()).unwrap_or_default();
+                (group.clone(), requirements)
+            })
+            .collect();
+        (resolved, cycles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pep508_rs::{PackageName, Requirement};
+    use std::str::FromStr;
+
+    use super::{InstallTarget, OptionalDependencies, ResolveErrorKind, ResolveOptions};
+    use crate::{DependencyGroupSpecifier, PyProjectToml};
+
+    fn requirements(requirements: &[&str]) -> Vec<Requirement> {
+        requirements
+            .iter()
+            .map(|requirement| Requirement::from_str(requirement).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_resolve_large_group() {
+        let names: Vec<String> = (0..5000).map(|i| format!("package-{i}>=1.{i}")).collect();
+        let source = format!(
+            "[project]\nname = \"spam\"\n\n[project.optional-dependencies]\ntest = [\"pytest\"]\n\n[dependency-groups]\nlarge = {names:?}\ndev = [\"spam[test] ; os_name == 'nt'\", {{include-group = \"large\"}}]\n"
+        );
+        let project_toml = PyProjectToml::new(&source).unwrap();
+        let resolved = project_toml.resolve().unwrap();
+
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let large = requirements(&names);
+        assert_eq!(resolved.dependency_groups["large"], large);
+        assert_eq!(
+            resolved.dependency_groups["dev"][0],
+            Requirement::from_str("pytest ; os_name == 'nt'").unwrap()
+        );
+        assert_eq!(resolved.dependency_groups["dev"][1..], large);
+        assert_eq!(
+            project_toml.dependency_groups.unwrap().resolve().unwrap()["large"],
+            large
+        );
+    }
+
+    #[test]
+    fn test_resolve_full() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+test = ["pytest", "spam[typing]"]
+typing = ["mypy"]
+
+[dependency-groups]
+lint = ["ruff"]
+dev = ["Spam[test]", {include-group = "lint"}, "pre-commit"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let full = project_toml.resolve_full().unwrap();
+        assert_eq!(full.dependencies, project_toml.resolve().unwrap());
+
+        let resolved = &full.dependencies;
+        assert_eq!(
+            resolved.optional_dependencies["test"],
+            requirements(&["pytest", "mypy"])
+        );
+        assert_eq!(
+            resolved.dependency_groups["dev"],
+            requirements(&["pytest", "mypy", "ruff", "pre-commit"])
+        );
+        assert_eq!(
+            full.includes[&InstallTarget::Group("dev".to_string())],
+            vec![
+                InstallTarget::Extra("test".to_string()),
+                InstallTarget::Group("lint".to_string())
+            ]
+        );
+
+        // Everything an included target resolves to is part of the including target
+        for (target, includes) in &full.includes {
+            let flat = match target {
+                InstallTarget::Extra(extra) => &resolved.optional_dependencies[extra],
+                InstallTarget::Group(group) => &resolved.dependency_groups[group],
+            };
+            for included in includes {
+                let included = match included {
+                    InstallTarget::Extra(extra) => &resolved.optional_dependencies[extra],
+                    InstallTarget::Group(group) => &resolved.dependency_groups[group],
+                };
+                assert!(included
+                    .iter()
+                    .all(|requirement| flat.contains(requirement)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_resolve_sorted_output() {
+        let source = r#"[dependency-groups]
+zeta = ["ruff"]
+alpha = [{include-group = "zeta"}]
+mu = ["pytest"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let sorted = project_toml.resolve_sorted_output().unwrap();
+        assert!(sorted.optional_dependencies.is_empty());
+        assert_eq!(
+            sorted.dependency_groups.keys().collect::<Vec<_>>(),
+            ["alpha", "mu", "zeta"]
+        );
+        assert_eq!(sorted.dependency_groups["alpha"], requirements(&["ruff"]));
+    }
+
+    #[test]
+    fn test_empty_targets() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+leftover = []
+test = ["pytest"]
+
+[dependency-groups]
+empty = []
+alias = [{include-group = "empty"}]
+lint = ["ruff"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        assert_eq!(
+            project_toml.empty_targets(),
+            vec![
+                InstallTarget::Extra("leftover".to_string()),
+                InstallTarget::Group("empty".to_string())
+            ]
+        );
+        assert_eq!(
+            project_toml.resolves_empty_targets().unwrap(),
+            vec![
+                InstallTarget::Extra("leftover".to_string()),
+                InstallTarget::Group("empty".to_string()),
+                InstallTarget::Group("alias".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_extras() {
+        let source = r#"[dependency-groups]
+a = ["pkg[a]", "other"]
+b = ["pkg[b]", "pkg[a]", "pkg[c]>=2"]
+all = [{include-group = "a"}, {include-group = "b"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let mut resolved = project_toml.resolve().unwrap();
+        resolved.merge_extras();
+        assert_eq!(
+            resolved.dependency_groups["all"],
+            requirements(&["pkg[a,b]", "other", "pkg[c]>=2"])
+        );
+        assert_eq!(
+            resolved.dependency_groups["b"],
+            requirements(&["pkg[b,a]", "pkg[c]>=2"])
+        );
+    }
+
+    #[test]
+    fn test_resolve_propagates_markers() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+test = ["pytest", "coverage; sys_platform == 'linux'"]
+
+[dependency-groups]
+dev = ["spam[test]; python_version >= '3.10'", "ruff"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let resolved = project_toml.resolve().unwrap();
+        assert_eq!(
+            resolved.dependency_groups["dev"],
+            requirements(&[
+                "pytest; python_version >= '3.10'",
+                "coverage; sys_platform == 'linux' and python_version >= '3.10'",
+                "ruff"
+            ])
+        );
+        assert_eq!(
+            resolved.optional_dependencies["test"],
+            requirements(&["pytest", "coverage; sys_platform == 'linux'"])
+        );
+    }
+
+    #[test]
+    fn test_duplicate_in_resolved() {
+        let source = r#"[dependency-groups]
+unit = ["pytest", "hypothesis"]
+integration = ["pytest", "requests"]
+all = [{include-group = "unit"}, {include-group = "integration"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let duplicates = project_toml.resolve().unwrap().duplicate_in_resolved();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(
+            duplicates["group:all"],
+            vec![PackageName::from_str("pytest").unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_to_dependency_groups() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+test = ["pytest"]
+
+[dependency-groups]
+lint = ["ruff"]
+dev = [{include-group = "lint"}, "spam[test]", "mypy"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let resolved = project_toml.resolve().unwrap();
+        let groups = resolved.to_dependency_groups();
+        assert!(groups
+            .values()
+            .flatten()
+            .all(|specifier| matches!(specifier, DependencyGroupSpecifier::String(_))));
+
+        let flattened = PyProjectToml {
+            dependency_groups: Some(groups),
+            ..project_toml
+        };
+        assert_eq!(
+            flattened.resolve().unwrap().dependency_groups,
+            resolved.dependency_groups
+        );
+    }
+
+    #[test]
+    fn test_requires_dist_lines() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+GUI = [
+  "pywin32; sys_platform == 'win32'",
+  "pyobjc; sys_platform == 'darwin' or sys_platform == 'ios'",
+  "qtpy",
+]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        assert_eq!(
+            project_toml.resolve().unwrap().requires_dist_lines(),
+            [
+                "Requires-Dist: pywin32 ; sys_platform == 'win32' and extra == 'gui'",
+                "Requires-Dist: pyobjc ; (sys_platform == 'darwin' and extra == 'gui') or (sys_platform == 'ios' and extra == 'gui')",
+                "Requires-Dist: qtpy ; extra == 'gui'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_self_reference_normalized_name() {
+        let source = r#"[project]
+name = "Spam.Eggs"
+
+[project.optional-dependencies]
+test = ["pytest"]
+all = ["spam_eggs[test]", "SPAM-EGGS[test]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        assert_eq!(
+            project_toml
+                .project
+                .as_ref()
+                .unwrap()
+                .normalized_name()
+                .unwrap(),
+            PackageName::from_str("spam-eggs").unwrap()
+        );
+        assert_eq!(
+            project_toml.resolve().unwrap().optional_dependencies["all"],
+            requirements(&["pytest", "pytest"])
+        );
+    }
+
+    #[test]
+    fn test_all_dependencies() {
+        let source = r#"[project]
+name = "spam"
+dependencies = ["httpx", "rich"]
+
+[project.optional-dependencies]
+test = ["pytest", "httpx", "coverage; sys_platform == 'linux'"]
+cov = ["coverage", "pytest"]
+all = ["spam[test,cov]"]
+
+[dependency-groups]
+dev = ["ruff"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let resolved = project_toml.resolve().unwrap();
+        assert_eq!(
+            project_toml
+                .project
+                .as_ref()
+                .unwrap()
+                .all_dependencies(&resolved),
+            requirements(&[
+                "httpx",
+                "rich",
+                "pytest",
+                "coverage; sys_platform == 'linux'",
+                "coverage",
+            ])
+        );
+    }
+
+    #[test]
+    fn test_resolve_dedup_diamond() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+http = ["requests"]
+client = ["spam[http]", "urllib3"]
+server = ["spam[http]", "requests; sys_platform == 'linux'"]
+all = ["spam[client]", "spam[server]"]
+
+[dependency-groups]
+base = ["requests"]
+left = [{include-group = "base"}, "pytest"]
+right = [{include-group = "base"}, "requests[socks]"]
+dev = [{include-group = "left"}, {include-group = "right"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        assert_eq!(
+            project_toml.resolve().unwrap().dependency_groups["dev"],
+            requirements(&["requests", "pytest", "requests", "requests[socks]"])
+        );
+
+        let resolved = project_toml.resolve_dedup().unwrap();
+        assert_eq!(
+            resolved.dependency_groups["dev"],
+            requirements(&["requests", "pytest", "requests[socks]"])
+        );
+        assert_eq!(
+            resolved.optional_dependencies["all"],
+            requirements(&["requests", "urllib3", "requests; sys_platform == 'linux'"])
+        );
+    }
+
+    #[test]
+    fn test_assert_fully_resolved() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+test = ["pytest"]
+all = ["spam[test]", "spam"]
+
+[dependency-groups]
+dev = ["spam[test]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let mut resolved = project_toml.resolve().unwrap();
+        resolved.assert_fully_resolved("spam").unwrap();
+
+        resolved.dependency_groups["dev"] = requirements(&["spam[test]"]);
+        assert_eq!(
+            resolved
+                .assert_fully_resolved("Spam")
+                .unwrap_err()
+                .to_string(),
+            "The self-reference `spam[test]` in group:dev was not expanded"
+        );
+    }
+
+    #[test]
+    fn test_transitive_extras() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+c = ["numpy"]
+b = ["spam[c]"]
+a = ["spam[b]", "spam[c]"]
+all = ["spam[a]", "pandas"]
+cyclic = ["spam[loop]"]
+loop = ["spam[cyclic]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let extras = project_toml.project.unwrap().optional_dependencies.unwrap();
+        assert_eq!(
+            extras.transitive_extras("all", "spam").unwrap(),
+            vec!["a", "b", "c"]
+        );
+        assert!(extras.transitive_extras("c", "spam").unwrap().is_empty());
+        assert_eq!(
+            extras.transitive_extras("cyclic", "spam").unwrap_err().to_string(),
+            "Detected a cycle in `optional-dependencies` and `dependency-groups`: `extra:cyclic` -> `extra:loop` -> `extra:cyclic`"
+        );
+    }
+
+    #[test]
+    fn test_resolve_breaking_cycles() {
+        let source = r#"[dependency-groups]
+alpha = ["numpy", {include-group = "beta"}]
+beta = ["pandas", {include-group = "alpha"}]
+gamma = ["ruff", {include-group = "beta"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let (resolved, cycles) = project_toml
+            .dependency_groups
+            .unwrap()
+            .resolve_breaking_cycles(None);
+        assert_eq!(resolved["alpha"], requirements(&["numpy", "pandas"]));
+        assert_eq!(resolved["beta"], requirements(&["pandas"]));
+        assert_eq!(resolved["gamma"], requirements(&["ruff", "pandas"]));
+        assert_eq!(
+            cycles.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["`group:alpha` -> `group:beta` -> `group:alpha`"]
+        );
+    }
+
+    #[test]
+    fn test_resolve_breaking_cycles_self_reference() {
+        let source = r#"[dependency-groups]
+test = ["pytest"]
+lint = ["ruff", "spam[dev]"]
+dev = ["spam[test, lint]; python_version >= '3.10'", "mypy"]
+all = ["spam[docs]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let dependency_groups = project_toml.dependency_groups.unwrap();
+
+        let (resolved, cycles) = dependency_groups.resolve_breaking_cycles(Some("Spam"));
+        assert_eq!(resolved["test"], requirements(&["pytest"]));
+        assert_eq!(
+            resolved["lint"],
+            requirements(&["ruff", "pytest; python_version >= '3.10'", "mypy"])
+        );
+        assert_eq!(
+            resolved["dev"],
+            requirements(&["pytest; python_version >= '3.10'", "mypy"])
+        );
+        // There is no `docs` group, so it can only be an extra of the project
+        assert_eq!(resolved["all"], requirements(&["spam[docs]"]));
+        assert_eq!(
+            cycles.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec!["`group:lint` -> `group:dev` -> `group:lint`"]
+        );
+
+        // Without the project name, self-references are kept as they are
+        let (resolved, cycles) = dependency_groups.resolve_breaking_cycles(None);
+        assert_eq!(resolved["lint"], requirements(&["ruff", "spam[dev]"]));
+        assert!(cycles.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_normalized_names() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+Test_Extra = ["pytest"]
+
+[dependency-groups]
+group_one = ["ruff"]
+group-one = ["mypy"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        assert_eq!(
+            project_toml.resolve().unwrap_err().to_string(),
+            "`group:group_one` and `group:group-one` are the same name after PEP 685 normalization"
+        );
+
+        let mut project_toml = project_toml;
+        project_toml
+            .dependency_groups
+            .as_mut()
+            .unwrap()
+            .0
+            .swap_remove("group-one");
+        let resolved = project_toml.resolve().unwrap();
+        assert_eq!(
+            resolved.optional_dependencies["test-extra"],
+            requirements(&["pytest"])
+        );
+        assert_eq!(
+            resolved.dependency_groups["group-one"],
+            requirements(&["ruff"])
+        );
+    }
+
+    #[test]
+    fn test_resolve_with_options() {
+        let source = r#"[dependency-groups]
+group_one = ["pytest"]
+group_two = ["ruff", {include-group = "group-one"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let resolved = project_toml
+            .resolve_with_options(ResolveOptions::default())
+            .unwrap();
+        assert_eq!(
+            resolved.dependency_groups["group-two"],
+            requirements(&["ruff", "pytest"])
+        );
+        assert_eq!(
+            project_toml
+                .resolve_with_options(ResolveOptions { normalize: false })
+                .unwrap_err()
+                .to_string(),
+            "Failed to find dependency group `group-one` included by group:group_two"
+        );
+
+        let source = r#"[dependency-groups]
+group_one = ["pytest"]
+group-one = ["ruff"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let resolved = project_toml
+            .resolve_with_options(ResolveOptions { normalize: false })
+            .unwrap();
+        assert_eq!(
+            resolved.dependency_groups.keys().collect::<Vec<_>>(),
+            ["group_one", "group-one"]
+        );
+    }
+
+    #[test]
+    fn test_get_extra_and_group() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+my-extra = ["pytest"]
+
+[dependency-groups]
+Dev_Tools = ["ruff"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let resolved = project_toml.resolve().unwrap();
+        assert_eq!(
+            resolved.get_extra("My_Extra"),
+            Some(requirements(&["pytest"]).as_slice())
+        );
+        assert_eq!(
+            resolved.get_group("dev.tools"),
+            Some(requirements(&["ruff"]).as_slice())
+        );
+        assert_eq!(resolved.get_extra("Dev_Tools"), None);
+        assert_eq!(resolved.get_group("missing"), None);
+    }
+
+    #[test]
+    fn test_includes() {
+        let source = r#"[dependency-groups]
+all = [{include-group = "Dev"}, {include-group = "docs"}]
+dev = ["ruff", {include-group = "test"}]
+test = ["pytest", {include-group = "all"}]
+docs = ["sphinx"]
+broken = [{include-group = "missing"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let groups = project_toml.dependency_groups.unwrap();
+        assert_eq!(groups.includes("all"), ["Dev", "docs"]);
+        assert_eq!(groups.includes("docs"), Vec::<&str>::new());
+        assert_eq!(groups.includes("broken"), ["missing"]);
+        assert_eq!(
+            groups.includes_transitive("all").unwrap(),
+            ["dev", "test", "docs"]
+        );
+        assert_eq!(
+            groups.includes_transitive("test").unwrap(),
+            ["all", "dev", "docs"]
+        );
+        assert_eq!(
+            groups
+                .includes_transitive("broken")
+                .unwrap_err()
+                .to_string(),
+            "Failed to find dependency group `missing` included by group:broken"
+        );
+    }
+
+    #[test]
+    fn test_resolve_missing_extra() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+iota = ["spam[alpha]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let err = project_toml.resolve().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to find optional dependency `alpha` included by extra:iota"
+        );
+        let ResolveErrorKind::OptionalDependencyNotFound {
+            source_requirement, ..
+        } = err.kind()
+        else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(
+            source_requirement.as_deref(),
+            Some(&Requirement::from_str("spam[alpha]").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_resolve_group_missing_extra() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+iota = ["numpy"]
+
+[dependency-groups]
+dev = ["spam[alpha]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let err = project_toml.resolve().unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            ResolveErrorKind::GroupReferencesMissingExtra { group, extra }
+                if group == "dev" && extra == "alpha"
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Dependency group `dev` references extra `alpha` on the project, which is not defined"
+        );
+    }
+
+    #[test]
+    fn test_resolve_cycle() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+alpha = ["spam[iota]"]
+iota = ["spam[alpha]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        assert_eq!(
+            project_toml.resolve().unwrap_err().to_string(),
+            "Detected a cycle in `optional-dependencies` and `dependency-groups`: `extra:alpha` -> `extra:iota` -> `extra:alpha`"
+        );
+    }
+
+    #[test]
+    fn test_resolve_cycle_through_group() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+test = ["pytest", "spam[lint]"]
+lint = ["ruff", "spam[test]"]
+
+[dependency-groups]
+dev = [{include-group = "ci"}]
+ci = ["spam[test]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let err = project_toml.resolve().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Detected a cycle in `optional-dependencies` and `dependency-groups`: `group:dev` -> `group:ci` -> `extra:test` -> `extra:lint` -> `extra:test`"
+        );
+        let ResolveErrorKind::Cycle(cycle) = err.kind() else {
+            panic!("expected a cycle, got {err}");
+        };
+        assert_eq!(cycle.path()[0], InstallTarget::Group("dev".to_string()));
+        assert_eq!(
+            cycle.targets(),
+            [
+                InstallTarget::Extra("test".to_string()),
+                InstallTarget::Extra("lint".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "include-extra")]
+    fn test_resolve_include_extra() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+test = ["pytest"]
+
+[dependency-groups]
+dev = ["ruff", {include-extra = "Test"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let full = project_toml.resolve_full().unwrap();
+        assert_eq!(
+            full.dependencies.dependency_groups["dev"],
+            requirements(&["ruff", "pytest"])
+        );
+        assert_eq!(
+            full.includes[&InstallTarget::Group("dev".to_string())],
+            [InstallTarget::Extra("test".to_string())]
+        );
+
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+test = ["pytest", "spam[lint]"]
+lint = ["ruff", "spam[test]"]
+
+[dependency-groups]
+dev = [{include-extra = "test"}]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        assert_eq!(
+            project_toml.resolve().unwrap_err().to_string(),
+            "Detected a cycle in `optional-dependencies` and `dependency-groups`: `group:dev` -> `extra:test` -> `extra:lint` -> `extra:test`"
+        );
+    }
+
+    #[test]
+    fn test_resolve_self_closure() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+test = ["pytest", "spam[typing]"]
+docs = ["sphinx"]
+typing = ["mypy"]
+all = ["spam[test]", "Spam[docs]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let optional_dependencies = project_toml.project.unwrap().optional_dependencies.unwrap();
+        let closure = optional_dependencies.resolve_self_closure("spam");
+        assert_eq!(closure["all"], vec!["test", "typing", "docs"]);
+        assert_eq!(closure["test"], vec!["typing"]);
+        assert!(closure["docs"].is_empty());
+        assert!(optional_dependencies.self_closure_cycles("spam").is_empty());
+
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+all = ["spam[test]"]
+test = ["pytest", "spam[lint]"]
+lint = ["ruff", "spam[test]"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let optional_dependencies = project_toml.project.unwrap().optional_dependencies.unwrap();
+        let closure = optional_dependencies.resolve_self_closure("spam");
+        assert_eq!(closure["all"], vec!["test", "lint"]);
+        assert_eq!(closure["test"], vec!["lint"]);
+        assert_eq!(closure["lint"], vec!["test"]);
+        let cycles: Vec<String> = optional_dependencies
+            .self_closure_cycles("spam")
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            cycles,
+            ["`extra:all` -> `extra:test` -> `extra:lint` -> `extra:test`"]
+        );
+    }
+
+    #[test]
+    fn test_pure_self_reference_groups() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+test = ["pytest"]
+docs = ["sphinx"]
+
+[dependency-groups]
+alias = ["spam[test]", "Spam[docs]"]
+nested = [{include-group = "alias"}, "spam[test]"]
+mixed = ["spam[test]", "ruff"]
+plain = ["spam"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let dependency_groups = project_toml.dependency_groups.unwrap();
+        assert_eq!(
+            dependency_groups.pure_self_reference_groups("spam"),
+            vec!["alias", "nested"]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Parsing the key fields of a `pyproject.toml` together with their location in the source.
+
+use pep440_rs::{Version, VersionSpecifiers};
+use pep508_rs::Requirement;
+use serde::Deserialize;
+use toml::Spanned;
+
+use crate::PyProjectToml;
+
+/// The fields of a `pyproject.toml` that downstream tools most often report errors for, with
+/// the byte range each value was read from, returned by [`PyProjectToml::new_spanned`].
+///
+/// Other tables and keys are not read, use [`PyProjectToml::new`] for them.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct SpannedPyProjectToml {
+    /// Build-related data
+    pub build_system: Option<SpannedBuildSystem>,
+    /// Project metadata
+    pub project: Option<SpannedProject>,
+}
+
+/// The `[build-system]` section with spans, see [`SpannedPyProjectToml`]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct SpannedBuildSystem {
+    /// PEP 508 dependencies required to execute the build system
+    #[serde(default)]
+    pub requires: Vec<Spanned<Requirement>>,
+}
+
+/// The `[project]` section with spans, see [`SpannedPyProjectToml`]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct SpannedProject {
+    /// The version of the project
+    pub version: Option<Spanned<Version>>,
+    /// The Python version requirements of the project
+    pub requires_python: Option<Spanned<VersionSpecifiers>>,
+    /// Project dependencies
+    pub dependencies: Option<Vec<Spanned<Requirement>>>,
+}
+
+impl PyProjectToml {
+    /// Parse `pyproject.toml` content, keeping the location of `build-system.requires`,
+    /// `project.version`, `project.requires-python` and `project.dependencies` values, e.g. to
+    /// underline them in an editor.
+    ///
+    /// Errors in these fields point to the offending value like the errors of
+    /// [`PyProjectToml::new`] do. Each span is a byte range into `content`.
+    pub fn new_spanned(content: &str) -> Result<SpannedPyProjectToml, toml::de::Error> {
+        toml::de::from_str(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PyProjectToml;
+
+    #[test]
+    fn test_new_spanned() {
+        let source = r#"[project]
+name = "spam"
+version = "1.0"
+dependencies = ["httpx", "gidgethub[httpx]>4.0.0"]
+"#;
+        let spanned = PyProjectToml::new_spanned(source).unwrap();
+        let project = spanned.project.unwrap();
+        let version = project.version.unwrap();
+        assert_eq!(&source[version.span()], "\"1.0\"");
+        let dependencies = project.dependencies.unwrap();
+        assert_eq!(
+            &source[dependencies[1].span()],
+            "\"gidgethub[httpx]>4.0.0\""
+        );
+
+        let source = r#"[project]
+name = "spam"
+dependencies = ["spam >=>1"]
+"#;
+        let err = PyProjectToml::new_spanned(source).unwrap_err();
+        assert_eq!(err.span(), Some(39..52));
+        let header: Vec<_> = err
+            .to_string()
+            .lines()
+            .take(4)
+            .map(str::to_string)
+            .collect();
+        assert_eq!(
+            header,
+            [
+                "TOML parse error at line 3, column 16",
+                "  |",
+                "3 | dependencies = [\"spam >=>1\"]",
+                "  |                ^^^^^^^^^^^^^",
+            ]
+        );
+    }
+}
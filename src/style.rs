@@ -0,0 +1,176 @@
+//! Source-level style checks on `pyproject.toml` content, independent of parsing.
+
+use std::fmt::{Display, Formatter};
+
+use crate::PyProjectToml;
+
+/// A formatting issue in the raw `pyproject.toml` content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StyleLint {
+    /// The line is indented with a tab
+    TabIndentation {
+        /// The line number, starting at 1
+        line: usize,
+    },
+    /// The line ends with spaces or tabs
+    TrailingWhitespace {
+        /// The line number, starting at 1
+        line: usize,
+    },
+}
+
+impl Display for StyleLint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StyleLint::TabIndentation { line } => {
+                write!(f, "Line {line} is indented with a tab")
+            }
+            StyleLint::TrailingWhitespace { line } => {
+                write!(f, "Line {line} has trailing whitespace")
+            }
+        }
+    }
+}
+
+/// Where a position in the TOML source is, to find the lines inside multi-line strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Context {
+    /// Keys, tables, values other than strings, and comments
+    Code,
+    BasicString,
+    LiteralString,
+    MultiLineBasicString,
+    MultiLineLiteralString,
+}
+
+impl Context {
+    /// The context at the end of `line`, if the line starts in this context.
+    fn after_line(self, line: &str) -> Self {
+        // The delimiters are ASCII, so they can't match inside a multi-byte character
+        let line = line.as_bytes();
+        let mut context = self;
+        let mut pos = 0;
+        while pos < line.len() {
+            let rest = &line[pos..];
+            match context {
+                Context::Code => match rest[0] {
+                    // The rest of the line is a comment
+                    b'#' => break,
+                    b'"' if rest.starts_with(b"\"\"\"") => {
+                        context = Context::MultiLineBasicString;
+                        pos += 3;
+                        continue;
+                    }
+                    b'"' => context = Context::BasicString,
+                    b'\'' if rest.starts_with(b"'''") => {
+                        context = Context::MultiLineLiteralString;
+                        pos += 3;
+                        continue;
+                    }
+                    b'\'' => context = Context::LiteralString,
+                    _ => {}
+                },
+                // An escaped character, such as `\"`, never ends a basic string
+                Context::BasicString | Context::MultiLineBasicString if rest[0] == b'\\' => {
+                    pos += 2;
+                    continue;
+                }
+                Context::BasicString if rest[0] == b'"' => context = Context::Code,
+                Context::LiteralString if rest[0] == b'\'' => context = Context::Code,
+                Context::MultiLineBasicString if rest.starts_with(b"\"\"\"") => {
+                    // Up to two quotes before the closing delimiter are part of the string
+                    pos += rest.iter().take_while(|&&c| c == b'"').take(5).count();
+                    context = Context::Code;
+                    continue;
+                }
+                Context::MultiLineLiteralString if rest.starts_with(b"'''") => {
+                    pos += rest.iter().take_while(|&&c| c == b'\'').take(5).count();
+                    context = Context::Code;
+                    continue;
+                }
+                _ => {}
+            }
+            pos += 1;
+        }
+        // Single-line strings end with the line, even if they aren't closed
+        match context {
+            Context::BasicString | Context::LiteralString => Context::Code,
+            context => context,
+        }
+    }
+}
+
+impl PyProjectToml {
+    /// Check the raw `pyproject.toml` content for tab indentation and trailing whitespace, e.g.
+    /// for a formatter.
+    ///
+    /// The content doesn't need to be valid TOML. Lines inside multi-line strings are skipped,
+    /// since their whitespace is part of the value. Triple quotes in comments, in other strings
+    /// or escaped with a backslash don't start or end a multi-line string.
+    pub fn lint_toml_style(content: &str) -> Vec<StyleLint> {
+        let mut lints = Vec::new();
+        let mut context = Context::Code;
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            let starts_in_string = context != Context::Code;
+            context = context.after_line(line);
+
+            if !starts_in_string && line.starts_with([' ', '\t']) {
+                let indentation = &line[..line.len() - line.trim_start().len()];
+                if indentation.contains('\t') {
+                    lints.push(StyleLint::TabIndentation { line: line_number });
+                }
+            }
+            if context == Context::Code && line.ends_with([' ', '\t']) {
+                lints.push(StyleLint::TrailingWhitespace { line: line_number });
+            }
+        }
+        lints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StyleLint;
+    use crate::PyProjectToml;
+
+    #[test]
+    fn test_lint_toml_style() {
+        let source = "[project]\nname = \"spam\" \ndependencies = [\n\t\"httpx\",\n]\ndescription = \"\"\"\n\tkept  \n\"\"\"\n";
+        let lints = PyProjectToml::lint_toml_style(source);
+        assert_eq!(
+            lints,
+            vec![
+                StyleLint::TrailingWhitespace { line: 2 },
+                StyleLint::TabIndentation { line: 4 },
+            ]
+        );
+        assert_eq!(lints[1].to_string(), "Line 4 is indented with a tab");
+    }
+
+    #[test]
+    fn test_lint_toml_style_quotes() {
+        // Triple quotes in a comment, in another string or escaped don't start or end a string
+        let source = [
+            "[project]",
+            "name = \"spam\" # no \"\"\" here ",
+            "version = '1.0\"\"\"' ",
+            "description = \"\"\"",
+            "\ta \\\"\"\" b ''' ",
+            "\"\"\"",
+            "readme = 'README.md' ",
+            "summary = \"\"\"x\"\"\"\" ",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(
+            PyProjectToml::lint_toml_style(&source),
+            vec![
+                StyleLint::TrailingWhitespace { line: 2 },
+                StyleLint::TrailingWhitespace { line: 3 },
+                StyleLint::TrailingWhitespace { line: 7 },
+                StyleLint::TrailingWhitespace { line: 8 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Requirements that remember their original formatting, so that unmodified requirements can be
+//! written back exactly as the user wrote them.
+
+use indexmap::IndexMap;
+use pep508_rs::{Pep508Error, Requirement};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::{BuildSystem, InstallTarget, PyProjectToml, ResolveError};
+
+/// An error from [`PyProjectToml::resolve_verbatim`]
+#[derive(Debug, Error)]
+pub enum VerbatimError {
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+    #[error(transparent)]
+    Resolve(#[from] ResolveError),
+}
+
+/// A PEP 508 requirement together with the string it was parsed from.
+///
+/// As long as the requirement is unmodified, it is displayed and serialized using the original
+/// string, e.g. `maturin >=1, <2` instead of `maturin>=1, <2`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerbatimRequirement {
+    requirement: Requirement,
+    given: Option<String>,
+}
+
+impl VerbatimRequirement {
+    /// Wrap a requirement that wasn't parsed from a string
+    pub fn new(requirement: Requirement) -> Self {
+        Self {
+            requirement,
+            given: None,
+        }
+    }
+
+    /// The string the requirement was parsed from
+    pub fn given(&self) -> Option<&str> {
+        self.given.as_deref()
+    }
+
+    /// Mutable access to the requirement. Once modified, the original string is no longer used.
+    pub fn requirement_mut(&mut self) -> &mut Requirement {
+        &mut self.requirement
+    }
+
+    /// Discard the original string
+    pub fn into_requirement(self) -> Requirement {
+        self.requirement
+    }
+
+    /// The original string if the requirement is unmodified
+    fn verbatim(&self) -> Option<&str> {
+        let given = self.given.as_deref()?;
+        match Requirement::from_str(given) {
+            Ok(requirement) if requirement == self.requirement => Some(given),
+            _ => None,
+        }
+    }
+}
+
+impl Deref for VerbatimRequirement {
+    type Target = Requirement;
+
+    fn deref(&self) -> &Self::Target {
+        &self.requirement
+    }
+}
+
+impl FromStr for VerbatimRequirement {
+    type Err = Pep508Error;
+
+    fn from_str(given: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            requirement: Requirement::from_str(given)?,
+            given: Some(given.to_string()),
+        })
+    }
+}
+
+/// Display the original string if the requirement is unmodified, otherwise the requirement.
+impl Display for VerbatimRequirement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.verbatim() {
+            Some(given) => f.write_str(given),
+            None => write!(f, "{}", self.requirement),
+        }
+    }
+}
+
+impl Serialize for VerbatimRequirement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for VerbatimRequirement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let given = String::deserialize(deserializer)?;
+        Self::from_str(&given).map_err(D::Error::custom)
+    }
+}
+
+/// The `[build-system]` section like [`BuildSystem`], but keeping the original formatting of the
+/// requirements.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct VerbatimBuildSystem {
+    /// PEP 508 dependencies required to execute the build system
+    pub requires: Vec<VerbatimRequirement>,
+    /// A string naming a Python object that will be used to perform the build
+    pub build_backend: Option<String>,
+    /// Specify that their backend code is hosted in-tree, this key contains a list of directories
+    pub backend_path: Option<Vec<String>>,
+}
+
+impl From<VerbatimBuildSystem> for BuildSystem {
+    fn from(build_system: VerbatimBuildSystem) -> Self {
+        Self {
+            requires: build_system
+                .requires
+                .into_iter()
+                .map(VerbatimRequirement::into_requirement)
+                .collect(),
+            build_backend: build_system.build_backend,
+            backend_path: build_system.backend_path,
+        }
+    }
+}
+
+impl PyProjectToml {
+    /// Parse only the `[build-system]` section of `pyproject.toml` content, keeping the original
+    /// formatting of the build requirements.
+    pub fn verbatim_build_system(
+        content: &str,
+    ) -> Result<Option<VerbatimBuildSystem>, toml::de::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "kebab-case")]
+        struct Wrapper {
+            build_system: Option<VerbatimBuildSystem>,
+        }
+
+        Ok(toml::de::from_str::<Wrapper>(content)?.build_system)
+    }
+
+    /// Like [`PyProjectToml::resolve`], but render each requirement as it was written in
+    /// `content`, the source this was parsed from, e.g. `pytest >= 8` instead of `pytest>=8`.
+    ///
+    /// The keys are the extras and groups displayed as `extra:<name>` or `group:<name>`.
+    /// Requirements that resolution changed, such as those that got the marker of a conditional
+    /// self-reference, are rendered normally. Returns an error if `content` is not valid TOML.
+    pub fn resolve_verbatim(
+        &self,
+        content: &str,
+    ) -> Result<IndexMap<String, Vec<String>>, VerbatimError> {
+        let table = toml::from_str::<toml::Table>(content)?;
+        let optional_dependencies = table
+            .get("project")
+            .and_then(|project| project.get("optional-dependencies"))
+            .and_then(toml::Value::as_table);
+        let dependency_groups = table
+            .get("dependency-groups")
+            .and_then(toml::Value::as_table);
+        let originals: Vec<(Requirement, &str)> = optional_dependencies
+            .into_iter()
+            .chain(dependency_groups)
+            .flat_map(|targets| targets.values())
+            .filter_map(toml::Value::as_array)
+            .flatten()
+            .filter_map(toml::Value::as_str)
+            .filter_map(|given| Some((Requirement::from_str(given).ok()?, given)))
+            .collect();
+        let render = |requirement: &Requirement| {
+            originals
+                .iter()
+                .find(|(original, _)| original == requirement)
+                .map_or_else(|| requirement.to_string(), |(_, given)| given.to_string())
+        };
+
+        let resolved = self.resolve()?;
+        let extras = resolved
+            .optional_dependencies
+            .iter()
+            .map(|(extra, requirements)| (InstallTarget::Extra(extra.clone()), requirements));
+        let groups = resolved
+            .dependency_groups
+            .iter()
+            .map(|(group, requirements)| (InstallTarget::Group(group.clone()), requirements));
+        Ok(extras
+            .chain(groups)
+            .map(|(target, requirements)| {
+                (
+                    target.to_string(),
+                    requirements.iter().map(render).collect(),
+                )
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pep440_rs::VersionSpecifiers;
+    use pep508_rs::{Requirement, VerbatimUrl, VersionOrUrl};
+    use std::str::FromStr;
+
+    use super::VerbatimError;
+    use crate::PyProjectToml;
+
+    #[test]
+    fn test_build_system_round_trip() {
+        let source = r#"[build-system]
+requires = ["maturin >=1, <2", "setuptools_scm[toml]  >= 8"]
+build-backend = "maturin"
+"#;
+        let mut build_system = PyProjectToml::verbatim_build_system(source)
+            .unwrap()
+            .unwrap();
+        let value = toml::Value::try_from(&build_system).unwrap();
+        assert_eq!(
+            value["requires"],
+            toml::Value::Array(vec![
+                "maturin >=1, <2".into(),
+                "setuptools_scm[toml]  >= 8".into()
+            ])
+        );
+
+        // Modified requirements are serialized normally
+        build_system.requires[0].requirement_mut().version_or_url = Some(
+            VersionOrUrl::VersionSpecifier(VersionSpecifiers::from_str(">=1.5,<2").unwrap()),
+        );
+        let value = toml::Value::try_from(&build_system).unwrap();
+        assert_eq!(
+            value["requires"][0].as_str(),
+            Some(
+                Requirement::<VerbatimUrl>::from_str("maturin>=1.5,<2")
+                    .unwrap()
+                    .to_string()
+                    .as_str()
+            )
+        );
+        assert_eq!(
+            value["requires"][1].as_str(),
+            Some("setuptools_scm[toml]  >= 8")
+        );
+    }
+
+    #[test]
+    fn test_resolve_verbatim() {
+        let source = r#"[project]
+name = "spam"
+
+[project.optional-dependencies]
+lint = ["ruff  ==0.6.9"]
+test = ["pytest >= 8", "spam[lint]"]
+
+[dependency-groups]
+dev = [{include-group = "docs"}, "spam[test]"]
+docs = ["sphinx ~= 8.0"]
+"#;
+        let project_toml = PyProjectToml::new(source).unwrap();
+        let resolved = project_toml.resolve_verbatim(source).unwrap();
+        assert_eq!(resolved["extra:test"], ["pytest >= 8", "ruff  ==0.6.9"]);
+        assert_eq!(
+            resolved["group:dev"],
+            ["sphinx ~= 8.0", "pytest >= 8", "ruff  ==0.6.9"]
+        );
+
+        assert!(matches!(
+            project_toml.resolve_verbatim("[project"),
+            Err(VerbatimError::Toml(_))
+        ));
+    }
+}